@@ -21,7 +21,7 @@ fn insertions(c: &mut Criterion) {
             "insert range block size of 1000",
             |b, &input_size| {
                 b.iter(|| {
-                    let mut l = SortedList::new(1000);
+                    let mut l = SortedList::with_block_size(1000);
                     for e in 0u64..input_size {
                         l.insert(e);
                     }
@@ -32,7 +32,7 @@ fn insertions(c: &mut Criterion) {
         )
         .with_function("insert range block size of sqrt(n)", |b, &input_size| {
             b.iter(|| {
-                let mut l = SortedList::new((input_size as f64).sqrt().ceil() as usize);
+                let mut l = SortedList::with_block_size((input_size as f64).sqrt().ceil() as usize);
                 for e in 0u64..input_size {
                     l.insert(e);
                 }
@@ -51,7 +51,7 @@ fn insertions(c: &mut Criterion) {
                 b.iter_with_setup(
                     || random_vec(input_size),
                     |v| {
-                        let mut l = SortedList::new(1000);
+                        let mut l = SortedList::with_block_size(1000);
                         for e in v {
                             l.insert(e);
                         }
@@ -75,7 +75,7 @@ fn insertions(c: &mut Criterion) {
             |b, &input_size| {
                 b.iter_with_setup(
                     || {
-                        let mut l = SortedList::new(1000);
+                        let mut l = SortedList::with_block_size(1000);
                         for e in random_vec(input_size) {
                             l.insert(e);
                         }
@@ -110,7 +110,7 @@ fn insertions(c: &mut Criterion) {
             |b, &input_size| {
                 b.iter_with_setup(
                     || {
-                        let mut l = SortedList::new(1000);
+                        let mut l = SortedList::with_block_size(1000);
                         for e in random_vec(input_size) {
                             l.insert(e);
                         }
@@ -149,7 +149,7 @@ fn insertions(c: &mut Criterion) {
             |b, &input_size| {
                 b.iter_with_setup(
                     || {
-                        let mut l = SortedList::new(1000);
+                        let mut l = SortedList::with_block_size(1000);
                         for e in random_vec(input_size) {
                             l.insert(e);
                         }
@@ -175,7 +175,7 @@ fn insertions(c: &mut Criterion) {
             |b, &input_size| {
                 b.iter_with_setup(
                     || {
-                        let mut l = SortedList::new(1000);
+                        let mut l = SortedList::with_block_size(1000);
                         for e in random_vec(input_size) {
                             l.insert(e);
                         }
@@ -215,5 +215,339 @@ fn insertions(c: &mut Criterion) {
     );
 }
 
-criterion_group!(benches, insertions);
+fn sorted_batch_insertions(c: &mut Criterion) {
+    let sizes = vec![1_000, 10_000, 20_000, 50_000];
+    c.bench(
+        "sorted batch insertions",
+        ParameterizedBenchmark::new(
+            "insert loop block size of 1000",
+            |b, &input_size| {
+                b.iter_with_setup(
+                    || {
+                        let mut l = SortedList::with_block_size(1000);
+                        for e in random_vec(input_size) {
+                            l.insert(2 * e);
+                        }
+                        l
+                    },
+                    |mut l| {
+                        for e in 0..input_size {
+                            l.insert(2 * e + 1);
+                        }
+                        l
+                    },
+                )
+            },
+            sizes.clone(),
+        )
+        .with_function("insert_sorted_vec block size of 1000", |b, &input_size| {
+            b.iter_with_setup(
+                || {
+                    let mut l = SortedList::with_block_size(1000);
+                    for e in random_vec(input_size) {
+                        l.insert(2 * e);
+                    }
+                    (l, (0..input_size).map(|e| 2 * e + 1).collect::<Vec<_>>())
+                },
+                |(mut l, v)| {
+                    l.insert_sorted_vec(v);
+                    l
+                },
+            )
+        }),
+    );
+}
+
+fn hinted_insertions(c: &mut Criterion) {
+    let sizes = vec![1_000, 10_000, 20_000, 50_000];
+    let nearly_sorted = |size: u64| {
+        let mut rng = rand::thread_rng();
+        (0..size)
+            .map(|e| e + rng.gen_range(0..100))
+            .collect::<Vec<_>>()
+    };
+    c.bench(
+        "hinted insertions",
+        ParameterizedBenchmark::new(
+            "insert range block size of 1000",
+            |b, &input_size| {
+                b.iter(|| {
+                    let mut l = SortedList::with_block_size(1000);
+                    for e in 0u64..input_size {
+                        l.insert(e);
+                    }
+                    l
+                })
+            },
+            sizes.clone(),
+        )
+        .with_function("insert_hint range block size of 1000", |b, &input_size| {
+            b.iter(|| {
+                let mut l = SortedList::with_block_size(1000);
+                let mut hint = None;
+                for e in 0u64..input_size {
+                    hint = Some(l.insert_hint(hint, e));
+                }
+                l
+            })
+        })
+        .with_function(
+            "insert nearly sorted block size of 1000",
+            move |b, &input_size| {
+                b.iter_with_setup(
+                    || nearly_sorted(input_size),
+                    |v| {
+                        let mut l = SortedList::with_block_size(1000);
+                        for e in v {
+                            l.insert(e);
+                        }
+                        l
+                    },
+                )
+            },
+        )
+        .with_function(
+            "insert_hint nearly sorted block size of 1000",
+            move |b, &input_size| {
+                b.iter_with_setup(
+                    || nearly_sorted(input_size),
+                    |v| {
+                        let mut l = SortedList::with_block_size(1000);
+                        let mut hint = None;
+                        for e in v {
+                            hint = Some(l.insert_hint(hint, e));
+                        }
+                        l
+                    },
+                )
+            },
+        ),
+    );
+}
+
+fn batch_insertions(c: &mut Criterion) {
+    let existing_size = 50_000;
+    let batch_sizes = vec![existing_size / 100, existing_size / 10, existing_size];
+    let setup = move |batch_size: u64| {
+        let mut l = SortedList::with_block_size(1000);
+        for e in random_vec(existing_size) {
+            l.insert(2 * e);
+        }
+        let batch = random_vec(batch_size)
+            .into_iter()
+            .map(|e| 2 * e + 1)
+            .collect::<Vec<_>>();
+        (l, batch)
+    };
+    c.bench(
+        "unsorted batch insertions in 50000 elements",
+        ParameterizedBenchmark::new(
+            "insert loop block size of 1000",
+            move |b, &batch_size| {
+                b.iter_with_setup(
+                    || setup(batch_size),
+                    |(mut l, v)| {
+                        for e in v {
+                            l.insert(e);
+                        }
+                        l
+                    },
+                )
+            },
+            batch_sizes,
+        )
+        .with_function("extend_batch block size of 1000", move |b, &batch_size| {
+            b.iter_with_setup(
+                || setup(batch_size),
+                |(mut l, v)| {
+                    l.extend_batch(v);
+                    l
+                },
+            )
+        }),
+    );
+}
+
+fn sorted_construction(c: &mut Criterion) {
+    let sizes = vec![1_000, 10_000, 20_000, 50_000];
+    c.bench(
+        "sorted construction",
+        ParameterizedBenchmark::new(
+            "insert loop block size of 1000",
+            |b, &input_size| {
+                b.iter_with_setup(
+                    || (0..input_size).collect::<Vec<u64>>(),
+                    |v| {
+                        let mut l = SortedList::with_block_size(1000);
+                        for e in v {
+                            l.insert(e);
+                        }
+                        l
+                    },
+                )
+            },
+            sizes.clone(),
+        )
+        .with_function("from_sorted_vec block size of 1000", |b, &input_size| {
+            b.iter_with_setup(
+                || (0..input_size).collect::<Vec<u64>>(),
+                |v| SortedList::from_sorted_vec(1000, v),
+            )
+        }),
+    );
+}
+
+fn vec_construction(c: &mut Criterion) {
+    let sizes = vec![1_000, 10_000, 20_000, 50_000];
+    c.bench(
+        "shuffled construction",
+        ParameterizedBenchmark::new(
+            "insert loop block size of 1000",
+            |b, &input_size| {
+                b.iter_with_setup(
+                    || random_vec(input_size),
+                    |v| {
+                        let mut l = SortedList::with_block_size(1000);
+                        for e in v {
+                            l.insert(e);
+                        }
+                        l
+                    },
+                )
+            },
+            sizes.clone(),
+        )
+        .with_function("from vec", |b, &input_size| {
+            b.iter_with_setup(|| random_vec(input_size), SortedList::from)
+        }),
+    );
+}
+
+fn islice(c: &mut Criterion) {
+    let window = 20;
+    let starts = vec![1_000, 10_000, 20_000, 49_000];
+    let list = (0..50_000u64).collect::<SortedList<u64>>();
+    let same_list = list.iter().cloned().collect::<SortedList<u64>>();
+    c.bench(
+        "window of 20 elements in 50000",
+        ParameterizedBenchmark::new(
+            "skip and take",
+            move |b, &start| b.iter(|| list.iter().skip(start).take(window).sum::<u64>()),
+            starts,
+        )
+        .with_function("islice", move |b, &start| {
+            b.iter(|| same_list.islice(start..start + window).sum::<u64>())
+        }),
+    );
+}
+
+fn nth(c: &mut Criterion) {
+    let positions = vec![1_000, 10_000, 100_000, 999_000];
+    let list = (0..1_000_000u64).collect::<SortedList<u64>>();
+    let same_list = list.iter().cloned().collect::<SortedList<u64>>();
+    c.bench(
+        "nth in 1000000 elements",
+        ParameterizedBenchmark::new(
+            "flattened blocks nth",
+            move |b, &n| b.iter(|| list.blocks().flatten().nth(n).cloned()),
+            positions,
+        )
+        .with_function("iter nth", move |b, &n| {
+            b.iter(|| same_list.iter().nth(n).cloned())
+        }),
+    );
+}
+
+fn sum(c: &mut Criterion) {
+    let sizes = vec![1_000, 10_000, 100_000, 1_000_000];
+    c.bench(
+        "sum",
+        ParameterizedBenchmark::new(
+            "sum vec",
+            |b, &input_size| {
+                let v = (0..input_size).collect::<Vec<u64>>();
+                b.iter(|| v.iter().sum::<u64>())
+            },
+            sizes,
+        )
+        .with_function("sum sortedlist", |b, &input_size| {
+            let l = (0..input_size).collect::<SortedList<u64>>();
+            b.iter(|| l.iter().sum::<u64>())
+        }),
+    );
+}
+
+fn merge_shards(c: &mut Criterion) {
+    let shard_counts = vec![2, 8, 32];
+    let shards = |count: u64| {
+        (0..count)
+            .map(|_| {
+                random_vec(100_000 / count)
+                    .into_iter()
+                    .collect::<SortedList<u64>>()
+            })
+            .collect::<Vec<_>>()
+    };
+    c.bench(
+        "merge 100000 elements in shards",
+        ParameterizedBenchmark::new(
+            "append",
+            move |b, &count| {
+                b.iter_with_setup(
+                    || shards(count),
+                    |shards| {
+                        let mut l = SortedList::new();
+                        for shard in shards {
+                            l.append(shard);
+                        }
+                        l
+                    },
+                )
+            },
+            shard_counts,
+        )
+        .with_function("merge_all", move |b, &count| {
+            b.iter_with_setup(|| shards(count), SortedList::merge_all)
+        }),
+    );
+}
+
+fn clone(c: &mut Criterion) {
+    let sizes = vec![1_000, 10_000, 100_000];
+    c.bench(
+        "clone",
+        ParameterizedBenchmark::new(
+            "clone",
+            |b, &input_size| {
+                let l = random_vec(input_size)
+                    .into_iter()
+                    .collect::<SortedList<u64>>();
+                b.iter(|| l.clone())
+            },
+            sizes,
+        )
+        .with_function("clone_from", |b, &input_size| {
+            let l = random_vec(input_size)
+                .into_iter()
+                .collect::<SortedList<u64>>();
+            let mut target = l.clone();
+            b.iter(|| target.clone_from(&l))
+        }),
+    );
+}
+
+criterion_group!(
+    benches,
+    insertions,
+    sorted_batch_insertions,
+    hinted_insertions,
+    batch_insertions,
+    sorted_construction,
+    vec_construction,
+    islice,
+    nth,
+    sum,
+    merge_shards,
+    clone
+);
 criterion_main!(benches);
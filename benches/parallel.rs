@@ -0,0 +1,60 @@
+#[macro_use]
+extern crate criterion;
+extern crate sortedlist;
+
+use criterion::{Criterion, ParameterizedBenchmark};
+use rand::prelude::*;
+use rayon::prelude::*;
+use sortedlist::SortedList;
+
+fn random_vec(size: u64) -> Vec<u64> {
+    let mut v = (0..size).collect::<Vec<_>>();
+    v.shuffle(&mut rand::thread_rng());
+    v
+}
+
+fn parallel_extend(c: &mut Criterion) {
+    let sizes = vec![10_000, 100_000, 1_000_000];
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(8)
+        .build()
+        .unwrap();
+    c.bench(
+        "extend 10000 elements",
+        ParameterizedBenchmark::new(
+            "extend",
+            |b, &input_size| {
+                b.iter_with_setup(
+                    || {
+                        (
+                            (0..10_000u64).collect::<SortedList<u64>>(),
+                            random_vec(input_size),
+                        )
+                    },
+                    |(mut l, v)| {
+                        l.extend(v.into_iter().map(|e| e * 3));
+                        l
+                    },
+                )
+            },
+            sizes,
+        )
+        .with_function("par_extend on 8 threads", move |b, &input_size| {
+            b.iter_with_setup(
+                || {
+                    (
+                        (0..10_000u64).collect::<SortedList<u64>>(),
+                        random_vec(input_size),
+                    )
+                },
+                |(mut l, v)| {
+                    pool.install(|| l.par_extend(v.into_par_iter().map(|e| e * 3)));
+                    l
+                },
+            )
+        }),
+    );
+}
+
+criterion_group!(benches, parallel_extend);
+criterion_main!(benches);
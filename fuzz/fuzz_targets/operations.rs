@@ -0,0 +1,21 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use sortedlist::SortedList;
+
+// Run random insertions and removals on a random list, checking it stays sorted.
+fuzz_target!(|input: (SortedList<u16>, Vec<(bool, u16)>)| {
+    let (mut list, operations) = input;
+    let mut len = list.len();
+    for (insert, value) in operations {
+        if insert {
+            list.insert(value);
+            len += 1;
+            assert!(list.contains(&value));
+        } else if list.remove(&value) {
+            len -= 1;
+        }
+        assert_eq!(list.len(), len);
+    }
+    assert_eq!(list.iter().count(), len);
+    assert!(list.adjacent_pairs().all(|(a, b)| a <= b));
+});
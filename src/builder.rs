@@ -0,0 +1,107 @@
+//! Builder for `SortedList`, gathering all construction knobs in one place.
+use super::{assert_valid_block_size, block_size_for, SortedList, DEFAULT_BLOCK_SIZE};
+
+/// Configure and build a `SortedList`.
+/// Defaults give the same list as `SortedList::default`.
+#[derive(Debug, Clone, Copy)]
+pub struct SortedListBuilder {
+    block_size: Option<usize>,
+    expected_len: Option<usize>,
+    fill_factor: f32,
+}
+
+impl Default for SortedListBuilder {
+    fn default() -> Self {
+        SortedListBuilder::new()
+    }
+}
+
+impl SortedListBuilder {
+    /// Create a builder with default settings.
+    pub fn new() -> Self {
+        SortedListBuilder {
+            block_size: None,
+            expected_len: None,
+            fill_factor: 1.0,
+        }
+    }
+
+    /// Set the block size, overriding the one derived from `expected_len`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is less than 2.
+    pub fn block_size(mut self, block_size: usize) -> Self {
+        assert_valid_block_size(block_size);
+        self.block_size = Some(block_size);
+        self
+    }
+
+    /// Announce how many elements the list will hold.
+    /// Unless given explicitly, the block size becomes sqrt(expected_len) (at least 16)
+    /// and room is reserved for all blocks.
+    pub fn expected_len(mut self, expected_len: usize) -> Self {
+        self.expected_len = Some(expected_len);
+        self
+    }
+
+    /// Set the proportion of each block filled when building a populated list
+    /// (1.0 by default). Leaving room speeds up following insertions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fill_factor` is not in (0, 1].
+    pub fn fill_factor(mut self, fill_factor: f32) -> Self {
+        assert!(
+            fill_factor > 0.0 && fill_factor <= 1.0,
+            "fill factor must be in (0, 1]"
+        );
+        self.fill_factor = fill_factor;
+        self
+    }
+
+    /// Build an empty list.
+    pub fn build<T: Ord>(self) -> SortedList<T> {
+        let block_size = self.chosen_block_size();
+        let mut list = SortedList::with_block_size(block_size);
+        if let Some(expected_len) = self.expected_len {
+            list.data
+                .reserve(expected_len.div_ceil(self.block_fill(block_size)));
+        }
+        list
+    }
+
+    /// Build a list from an already sorted vector, in O(n).
+    pub fn build_from_sorted<T: Ord>(self, data: Vec<T>) -> SortedList<T> {
+        debug_assert!(data.windows(2).all(|w| w[0] <= w[1]));
+        let mut list = self.build();
+        let fill = self.block_fill(list.block_size);
+        list.len = data.len();
+        list.data.reserve(data.len().div_ceil(fill));
+        let mut elements = data.into_iter().peekable();
+        while elements.peek().is_some() {
+            let mut block = Vec::with_capacity(list.block_size);
+            block.extend(elements.by_ref().take(fill));
+            list.data.push(block);
+        }
+        list
+    }
+
+    /// Build a list from any elements, by collecting and sorting them.
+    pub fn build_from_iter<T: Ord, I: IntoIterator<Item = T>>(self, iter: I) -> SortedList<T> {
+        let mut elements: Vec<T> = iter.into_iter().collect();
+        elements.sort_unstable();
+        self.build_from_sorted(elements)
+    }
+
+    fn chosen_block_size(&self) -> usize {
+        self.block_size
+            .or_else(|| self.expected_len.map(block_size_for))
+            .unwrap_or(DEFAULT_BLOCK_SIZE)
+    }
+
+    /// Number of elements we put in each block when building a populated list.
+    fn block_fill(&self, block_size: usize) -> usize {
+        ((block_size as f32 * self.fill_factor) as usize).max(1)
+    }
+}
@@ -0,0 +1,217 @@
+//! Cursors: positions in a `SortedList` which can be moved forward and backward.
+//!
+//! Like `LinkedList`'s cursors, a cursor points either to an element or to a "ghost"
+//! position standing both after the last element and before the first one.
+use super::SortedList;
+
+/// Read-only cursor over a `SortedList`.
+/// Obtained with `SortedList::cursor_at` or `SortedList::cursor_at_index`.
+pub struct Cursor<'a, T> {
+    list: &'a SortedList<T>,
+    block_index: usize,
+    element_index: usize,
+    index: usize,
+}
+
+impl<'a, T: Ord> Cursor<'a, T> {
+    pub(crate) fn new(list: &'a SortedList<T>, position: (usize, usize), index: usize) -> Self {
+        Cursor {
+            list,
+            block_index: position.0,
+            element_index: position.1,
+            index,
+        }
+    }
+
+    /// Return the element under the cursor (`None` on the ghost position).
+    pub fn peek(&self) -> Option<&'a T> {
+        self.list
+            .data
+            .get(self.block_index)
+            .map(|b| &b[self.element_index])
+    }
+
+    /// Return the position of the element under the cursor (`None` on the ghost position).
+    pub fn rank(&self) -> Option<usize> {
+        if self.index < self.list.len {
+            Some(self.index)
+        } else {
+            None
+        }
+    }
+
+    /// Move to the next element.
+    /// From the last element we move to the ghost position and from there to the first element.
+    pub fn move_next(&mut self) {
+        let (position, index) = next_position(self.list, self.position(), self.index);
+        self.set_position(position, index);
+    }
+
+    /// Move to the previous element.
+    /// From the first element we move to the ghost position and from there to the last element.
+    pub fn move_prev(&mut self) {
+        let (position, index) = prev_position(self.list, self.position(), self.index);
+        self.set_position(position, index);
+    }
+
+    fn position(&self) -> (usize, usize) {
+        (self.block_index, self.element_index)
+    }
+
+    fn set_position(&mut self, position: (usize, usize), index: usize) {
+        self.block_index = position.0;
+        self.element_index = position.1;
+        self.index = index;
+    }
+}
+
+/// Cursor over a `SortedList` which can also remove and insert elements.
+/// Obtained with `SortedList::cursor_mut_at` or `SortedList::cursor_mut_at_index`.
+pub struct CursorMut<'a, T> {
+    list: &'a mut SortedList<T>,
+    block_index: usize,
+    element_index: usize,
+    index: usize,
+}
+
+impl<'a, T: Ord> CursorMut<'a, T> {
+    pub(crate) fn new(list: &'a mut SortedList<T>, position: (usize, usize), index: usize) -> Self {
+        CursorMut {
+            list,
+            block_index: position.0,
+            element_index: position.1,
+            index,
+        }
+    }
+
+    /// Return the element under the cursor (`None` on the ghost position).
+    pub fn peek(&self) -> Option<&T> {
+        self.list
+            .data
+            .get(self.block_index)
+            .map(|b| &b[self.element_index])
+    }
+
+    /// Return the position of the element under the cursor (`None` on the ghost position).
+    pub fn rank(&self) -> Option<usize> {
+        if self.index < self.list.len {
+            Some(self.index)
+        } else {
+            None
+        }
+    }
+
+    /// Move to the next element.
+    /// From the last element we move to the ghost position and from there to the first element.
+    pub fn move_next(&mut self) {
+        let (position, index) = next_position(self.list, self.position(), self.index);
+        self.set_position(position, index);
+    }
+
+    /// Move to the previous element.
+    /// From the first element we move to the ghost position and from there to the last element.
+    pub fn move_prev(&mut self) {
+        let (position, index) = prev_position(self.list, self.position(), self.index);
+        self.set_position(position, index);
+    }
+
+    /// Remove and return the element under the cursor.
+    /// The cursor then points to the next element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cursor is on the ghost position.
+    pub fn remove_current(&mut self) -> T {
+        assert!(
+            self.block_index < self.list.data.len(),
+            "cannot remove the ghost position"
+        );
+        let blocks_count = self.list.data.len();
+        let block_len = self.list.data[self.block_index].len();
+        let element = self
+            .list
+            .remove_indexes(self.block_index, self.element_index);
+        if self.list.data.len() == blocks_count
+            && self.list.data[self.block_index].len() == block_len - 1
+        {
+            if self.element_index == block_len - 1 {
+                self.block_index += 1;
+                self.element_index = 0;
+            }
+        } else {
+            // blocks were merged or redistributed
+            let (block_index, element_index) = self.list.position_at(self.index);
+            self.block_index = block_index;
+            self.element_index = element_index;
+        }
+        element
+    }
+
+    /// Insert given element just before the cursor, which is not moved.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element does not fit between the element before the cursor
+    /// and the one under it.
+    pub fn insert(&mut self, element: T) {
+        let (block_index, element_index) = self.position();
+        assert!(
+            self.list
+                .element_before(block_index, element_index)
+                .is_none_or(|previous| *previous <= element)
+                && self.peek().is_none_or(|next| element <= *next),
+            "inserted element is out of order"
+        );
+        let (block_index, element_index) = self.list.insert_at(block_index, element_index, element);
+        self.index += 1;
+        if element_index + 1 == self.list.data[block_index].len() {
+            self.set_position((block_index + 1, 0), self.index);
+        } else {
+            self.set_position((block_index, element_index + 1), self.index);
+        }
+    }
+
+    fn position(&self) -> (usize, usize) {
+        (self.block_index, self.element_index)
+    }
+
+    fn set_position(&mut self, position: (usize, usize), index: usize) {
+        self.block_index = position.0;
+        self.element_index = position.1;
+        self.index = index;
+    }
+}
+
+/// Return the position and global index following given ones (cycling through the ghost).
+fn next_position<T>(
+    list: &SortedList<T>,
+    (block_index, element_index): (usize, usize),
+    index: usize,
+) -> ((usize, usize), usize) {
+    match list.data.get(block_index) {
+        // from the ghost to the first element (which is still the ghost if the list is empty)
+        None => ((0, 0), 0),
+        Some(block) if element_index + 1 < block.len() => {
+            ((block_index, element_index + 1), index + 1)
+        }
+        Some(_) => ((block_index + 1, 0), index + 1),
+    }
+}
+
+/// Return the position and global index preceding given ones (cycling through the ghost).
+fn prev_position<T>(
+    list: &SortedList<T>,
+    (block_index, element_index): (usize, usize),
+    index: usize,
+) -> ((usize, usize), usize) {
+    if element_index > 0 {
+        ((block_index, element_index - 1), index - 1)
+    } else if block_index == 0 {
+        // from the first element (or the ghost of an empty list) to the ghost
+        ((list.data.len(), 0), list.len)
+    } else {
+        // this also moves from the ghost to the last element
+        let previous = block_index - 1;
+        ((previous, list.data[previous].len() - 1), index - 1)
+    }
+}
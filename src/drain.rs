@@ -0,0 +1,186 @@
+//! Iterators removing elements from a `SortedList`.
+use super::SortedList;
+use std::iter::FusedIterator;
+
+/// Iterator removing and yielding (in order) all elements satisfying a predicate.
+/// Obtained with `SortedList::extract_if`.
+/// Elements not yet inspected when the iterator is dropped are kept.
+/// If the iterator is leaked (`mem::forget`) the list is left empty.
+pub struct ExtractIf<'a, T, F>
+where
+    T: Ord,
+    F: FnMut(&T) -> bool,
+{
+    list: &'a mut SortedList<T>,
+    // blocks are taken out of the list (left empty) and given back on drop
+    blocks: Vec<Vec<T>>,
+    predicate: F,
+    block_index: usize,
+    // while inside a block its length is set to 0 and we compact it ourselves:
+    // elements in [0, write) are kept, elements in [read, block_len) are not inspected yet.
+    read: usize,
+    write: usize,
+    block_len: Option<usize>,
+}
+
+impl<'a, T, F> ExtractIf<'a, T, F>
+where
+    T: Ord,
+    F: FnMut(&T) -> bool,
+{
+    pub(crate) fn new(list: &'a mut SortedList<T>, predicate: F) -> Self {
+        let blocks = std::mem::take(&mut list.data);
+        list.len = 0;
+        ExtractIf {
+            list,
+            blocks,
+            predicate,
+            block_index: 0,
+            read: 0,
+            write: 0,
+            block_len: None,
+        }
+    }
+
+    /// Give back to current block the elements we did not inspect yet.
+    fn close_block(&mut self) {
+        if let Some(block_len) = self.block_len.take() {
+            let block = &mut self.blocks[self.block_index];
+            unsafe {
+                let buffer = block.as_mut_ptr();
+                buffer
+                    .add(self.read)
+                    .copy_to(buffer.add(self.write), block_len - self.read);
+                block.set_len(self.write + block_len - self.read);
+            }
+            self.block_index += 1;
+            self.read = 0;
+            self.write = 0;
+        }
+    }
+}
+
+impl<'a, T, F> Iterator for ExtractIf<'a, T, F>
+where
+    T: Ord,
+    F: FnMut(&T) -> bool,
+{
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        while self.block_index < self.blocks.len() {
+            let block = &mut self.blocks[self.block_index];
+            let block_len = *self.block_len.get_or_insert_with(|| {
+                let block_len = block.len();
+                // if the predicate panics, drop still closes the block
+                // so no element is dropped twice
+                unsafe { block.set_len(0) };
+                block_len
+            });
+            let buffer = block.as_mut_ptr();
+            while self.read < block_len {
+                let current = unsafe { buffer.add(self.read) };
+                let extract = (self.predicate)(unsafe { &*current });
+                self.read += 1;
+                if extract {
+                    return Some(unsafe { current.read() });
+                } else {
+                    if self.read - 1 != self.write {
+                        unsafe { current.copy_to_nonoverlapping(buffer.add(self.write), 1) };
+                    }
+                    self.write += 1;
+                }
+            }
+            self.close_block();
+        }
+        None
+    }
+}
+
+impl<'a, T, F> Drop for ExtractIf<'a, T, F>
+where
+    T: Ord,
+    F: FnMut(&T) -> bool,
+{
+    fn drop(&mut self) {
+        self.close_block();
+        self.list.data = std::mem::take(&mut self.blocks);
+        // drops empty blocks and recomputes the length
+        self.list.consolidate();
+    }
+}
+
+/// Iterator removing and yielding (in order) all elements of a `SortedList`.
+/// Obtained with `SortedList::drain`.
+/// The list is empty even if the iterator is dropped before completion.
+pub struct Drain<'a, T> {
+    blocks: std::vec::Drain<'a, Vec<T>>,
+    current: std::vec::IntoIter<T>,
+    remaining: usize,
+}
+
+impl<'a, T: Ord> Drain<'a, T> {
+    pub(crate) fn new(list: &'a mut SortedList<T>) -> Self {
+        let remaining = std::mem::replace(&mut list.len, 0);
+        Drain {
+            blocks: list.data.drain(..),
+            current: Vec::new().into_iter(),
+            remaining,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Drain<'a, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        loop {
+            if let Some(element) = self.current.next() {
+                self.remaining -= 1;
+                return Some(element);
+            }
+            self.current = self.blocks.next()?.into_iter();
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> FusedIterator for Drain<'a, T> {}
+
+/// Iterator yielding (in order) all elements removed from a range of a `SortedList`.
+/// Obtained with `SortedList::drain_range` or `SortedList::drain_islice`.
+/// The range is cut out of the list (and boundary blocks fixed) right away,
+/// so the iterator owns the removed elements and does not borrow the list.
+pub struct DrainRange<T> {
+    blocks: std::vec::IntoIter<Vec<T>>,
+    current: std::vec::IntoIter<T>,
+    remaining: usize,
+}
+
+impl<T> DrainRange<T> {
+    pub(crate) fn new(removed_blocks: Vec<Vec<T>>) -> Self {
+        DrainRange {
+            remaining: removed_blocks.iter().map(|b| b.len()).sum(),
+            blocks: removed_blocks.into_iter(),
+            current: Vec::new().into_iter(),
+        }
+    }
+}
+
+impl<T> Iterator for DrainRange<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        loop {
+            if let Some(element) = self.current.next() {
+                self.remaining -= 1;
+                return Some(element);
+            }
+            self.current = self.blocks.next()?.into_iter();
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> FusedIterator for DrainRange<T> {}
@@ -0,0 +1,76 @@
+//! Entry API: look up a value once then insert, get, replace or remove it.
+use super::SortedList;
+
+/// A view into a single position of a `SortedList`, obtained with `SortedList::entry`.
+pub enum Entry<'a, T: Ord> {
+    /// An equal element is stored.
+    Occupied(OccupiedEntry<'a, T>),
+    /// No equal element is stored.
+    Vacant(VacantEntry<'a, T>),
+}
+
+/// An entry for an element which is stored.
+pub struct OccupiedEntry<'a, T: Ord> {
+    pub(crate) list: &'a mut SortedList<T>,
+    pub(crate) block_index: usize,
+    pub(crate) element_index: usize,
+}
+
+/// An entry for a value which is not stored, remembering where it should go.
+pub struct VacantEntry<'a, T: Ord> {
+    pub(crate) list: &'a mut SortedList<T>,
+    pub(crate) value: T,
+    pub(crate) block_index: usize,
+    pub(crate) element_index: usize,
+}
+
+impl<'a, T: Ord> Entry<'a, T> {
+    /// Return the stored element, inserting the entry's value if there is none.
+    pub fn or_insert(self) -> &'a T {
+        match self {
+            Entry::Occupied(entry) => entry.into_ref(),
+            Entry::Vacant(entry) => entry.insert(),
+        }
+    }
+}
+
+impl<'a, T: Ord> OccupiedEntry<'a, T> {
+    /// Return the stored element.
+    pub fn get(&self) -> &T {
+        &self.list.data[self.block_index][self.element_index]
+    }
+
+    /// Return the stored element, for the lifetime of the borrow of the list.
+    pub fn into_ref(self) -> &'a T {
+        &self.list.data[self.block_index][self.element_index]
+    }
+
+    /// Replace the stored element by given one (which must compare equal) and return it.
+    pub fn replace(&mut self, value: T) -> T {
+        let stored = &mut self.list.data[self.block_index][self.element_index];
+        debug_assert!(*stored == value);
+        std::mem::replace(stored, value)
+    }
+
+    /// Remove the stored element and return it.
+    pub fn remove(self) -> T {
+        self.list
+            .remove_indexes(self.block_index, self.element_index)
+    }
+}
+
+impl<'a, T: Ord> VacantEntry<'a, T> {
+    /// Return the value which would be inserted.
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Insert the value at the position found when creating the entry
+    /// and return a reference to it.
+    pub fn insert(self) -> &'a T {
+        let (block_index, element_index) =
+            self.list
+                .insert_at(self.block_index, self.element_index, self.value);
+        &self.list.data[block_index][element_index]
+    }
+}
@@ -0,0 +1,350 @@
+//! Iterators over the elements of a `SortedList`.
+use super::SortedList;
+use std::borrow::Borrow;
+use std::iter::FusedIterator;
+
+/// Iterator yielding (in order) references to all elements of a `SortedList`.
+/// Obtained with `SortedList::iter`.
+/// It can also be walked backwards.
+pub struct Iter<'a, T> {
+    blocks: std::slice::Iter<'a, Vec<T>>,
+    front: std::slice::Iter<'a, T>,
+    back: std::slice::Iter<'a, T>,
+    remaining: usize,
+}
+
+impl<'a, T> Iter<'a, T> {
+    pub(crate) fn new(list: &'a SortedList<T>) -> Self {
+        Iter {
+            blocks: list.data.iter(),
+            front: [].iter(),
+            back: [].iter(),
+            remaining: list.len,
+        }
+    }
+
+    /// Iterate on elements between given (block index, index in block) positions.
+    /// Only the covered blocks are touched.
+    pub(crate) fn between(
+        list: &'a SortedList<T>,
+        start: (usize, usize),
+        end: (usize, usize),
+    ) -> Self {
+        if start >= end {
+            return Iter {
+                blocks: [].iter(),
+                front: [].iter(),
+                back: [].iter(),
+                remaining: 0,
+            };
+        }
+        let (front, blocks, back) = if start.0 == end.0 {
+            (&list.data[start.0][start.1..end.1], &[][..], &[][..])
+        } else {
+            (
+                &list.data[start.0][start.1..],
+                &list.data[start.0 + 1..end.0],
+                list.data.get(end.0).map_or(&[][..], |b| &b[..end.1]),
+            )
+        };
+        Iter {
+            remaining: front.len() + blocks.iter().map(|b| b.len()).sum::<usize>() + back.len(),
+            blocks: blocks.iter(),
+            front: front.iter(),
+            back: back.iter(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            if let Some(element) = self.front.next() {
+                self.remaining -= 1;
+                return Some(element);
+            }
+            match self.blocks.next() {
+                Some(block) => self.front = block.iter(),
+                // remaining elements are in the block started from the back
+                None => {
+                    let element = self.back.next()?;
+                    self.remaining -= 1;
+                    return Some(element);
+                }
+            }
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+    /// Skip whole blocks using their lengths. This runs in O(number of blocks).
+    fn nth(&mut self, n: usize) -> Option<&'a T> {
+        if n >= self.remaining {
+            self.remaining = 0;
+            self.front = [].iter();
+            self.blocks = [].iter();
+            self.back = [].iter();
+            return None;
+        }
+        self.remaining -= n + 1;
+        let mut skipped = n;
+        if skipped < self.front.len() {
+            return self.front.nth(skipped);
+        }
+        skipped -= self.front.len();
+        for block in self.blocks.by_ref() {
+            if skipped < block.len() {
+                self.front = block.iter();
+                return self.front.nth(skipped);
+            }
+            skipped -= block.len();
+        }
+        self.front = [].iter();
+        self.back.nth(skipped)
+    }
+    fn count(self) -> usize {
+        self.remaining
+    }
+    fn last(mut self) -> Option<&'a T> {
+        self.next_back()
+    }
+    /// Loop directly on each block's slice.
+    fn fold<B, F: FnMut(B, &'a T) -> B>(self, init: B, mut f: F) -> B {
+        let mut accumulator = self.front.fold(init, &mut f);
+        for block in self.blocks {
+            accumulator = block.iter().fold(accumulator, &mut f);
+        }
+        self.back.fold(accumulator, f)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter {
+            blocks: self.blocks.clone(),
+            front: self.front.clone(),
+            back: self.back.clone(),
+            remaining: self.remaining,
+        }
+    }
+}
+
+/// Display the elements not yet yielded.
+impl<'a, T: std::fmt::Debug> std::fmt::Debug for Iter<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Iter(")?;
+        f.debug_list().entries(self.clone()).finish()?;
+        f.write_str(")")
+    }
+}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        loop {
+            if let Some(element) = self.back.next_back() {
+                self.remaining -= 1;
+                return Some(element);
+            }
+            match self.blocks.next_back() {
+                Some(block) => self.back = block.iter(),
+                None => {
+                    let element = self.front.next_back()?;
+                    self.remaining -= 1;
+                    return Some(element);
+                }
+            }
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SortedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Iter<'a, T> {
+        Iter::new(self)
+    }
+}
+
+/// Iterator yielding (in order) references to all elements of a `SortedList`,
+/// which can jump forward to a given value.
+/// Obtained with `SortedList::seek_iter`.
+pub struct SeekableIter<'a, T> {
+    list: &'a SortedList<T>,
+    block_index: usize,
+    element_index: usize,
+}
+
+impl<'a, T: Ord> SeekableIter<'a, T> {
+    pub(crate) fn new(list: &'a SortedList<T>) -> Self {
+        SeekableIter {
+            list,
+            block_index: 0,
+            element_index: 0,
+        }
+    }
+
+    /// Skip all elements smaller than given value.
+    /// We never move backwards: if the next element is already not smaller nothing happens.
+    pub fn seek<Q>(&mut self, value: &Q)
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let data = &self.list.data;
+        let block = match data.get(self.block_index) {
+            Some(block) => block,
+            None => return,
+        };
+        if block[block.len() - 1].borrow() < value {
+            // binary search on the following blocks
+            self.block_index += 1 + data[self.block_index + 1..]
+                .partition_point(|b| b[b.len() - 1].borrow() < value);
+            self.element_index = 0;
+            if self.block_index == data.len() {
+                return;
+            }
+        }
+        let block = &data[self.block_index];
+        self.element_index += block[self.element_index..].partition_point(|e| e.borrow() < value);
+    }
+}
+
+impl<'a, T> Iterator for SeekableIter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        let block = self.list.data.get(self.block_index)?;
+        let element = &block[self.element_index];
+        self.element_index += 1;
+        if self.element_index == block.len() {
+            self.block_index += 1;
+            self.element_index = 0;
+        }
+        Some(element)
+    }
+}
+
+impl<'a, T> FusedIterator for SeekableIter<'a, T> {}
+
+/// Iterator yielding (in order) references to all elements of two `SortedList`s.
+/// Obtained with `SortedList::merge`.
+pub struct Merge<'a, T> {
+    left: std::iter::Peekable<Iter<'a, T>>,
+    right: std::iter::Peekable<Iter<'a, T>>,
+}
+
+impl<'a, T> Merge<'a, T> {
+    pub(crate) fn new(left: &'a SortedList<T>, right: &'a SortedList<T>) -> Self {
+        Merge {
+            left: Iter::new(left).peekable(),
+            right: Iter::new(right).peekable(),
+        }
+    }
+}
+
+impl<'a, T: Ord> Iterator for Merge<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        match (self.left.peek(), self.right.peek()) {
+            (Some(l), Some(r)) if r < l => self.right.next(),
+            (Some(_), _) => self.left.next(),
+            (None, _) => self.right.next(),
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.left.len() + self.right.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: Ord> ExactSizeIterator for Merge<'a, T> {}
+
+impl<'a, T: Ord> FusedIterator for Merge<'a, T> {}
+
+/// Owning iterator yielding (in order) all elements of a `SortedList`.
+/// Obtained with `SortedList::into_iter`.
+/// Blocks are consumed one after the other (from both ends), without any new allocation.
+pub struct IntoIter<T> {
+    blocks: std::vec::IntoIter<Vec<T>>,
+    front: std::vec::IntoIter<T>,
+    back: std::vec::IntoIter<T>,
+    remaining: usize,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        loop {
+            if let Some(element) = self.front.next() {
+                self.remaining -= 1;
+                return Some(element);
+            }
+            match self.blocks.next() {
+                Some(block) => self.front = block.into_iter(),
+                // remaining elements are in the block started from the back
+                None => {
+                    let element = self.back.next()?;
+                    self.remaining -= 1;
+                    return Some(element);
+                }
+            }
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+    fn count(self) -> usize {
+        self.remaining
+    }
+    fn last(mut self) -> Option<T> {
+        self.next_back()
+    }
+    /// Loop directly on each block.
+    fn fold<B, F: FnMut(B, T) -> B>(self, init: B, mut f: F) -> B {
+        let mut accumulator = self.front.fold(init, &mut f);
+        for block in self.blocks {
+            accumulator = block.into_iter().fold(accumulator, &mut f);
+        }
+        self.back.fold(accumulator, f)
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        loop {
+            if let Some(element) = self.back.next_back() {
+                self.remaining -= 1;
+                return Some(element);
+            }
+            match self.blocks.next_back() {
+                Some(block) => self.back = block.into_iter(),
+                None => {
+                    let element = self.front.next_back()?;
+                    self.remaining -= 1;
+                    return Some(element);
+                }
+            }
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for SortedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            blocks: self.data.into_iter(),
+            front: Vec::new().into_iter(),
+            back: Vec::new().into_iter(),
+            remaining: self.len,
+        }
+    }
+}
@@ -1,5 +1,57 @@
 //! Implement python SortedList from sortedcontainers.
 use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
+
+#[cfg(feature = "rand")]
+use rand::Rng;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+mod drain;
+pub use drain::{Drain, DrainRange, ExtractIf};
+mod entry;
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
+mod builder;
+pub use builder::SortedListBuilder;
+mod iter;
+pub use iter::{IntoIter, Iter, Merge, SeekableIter};
+mod cursor;
+pub use cursor::{Cursor, CursorMut};
+#[cfg(feature = "rkyv")]
+mod rkyv_impls;
+#[cfg(feature = "serde")]
+mod serde_impls;
+#[cfg(feature = "rkyv")]
+pub use rkyv_impls::{ArchivedSortedList, SortedListResolver};
+
+/// Block size used by `SortedList::new` and `SortedList::default`.
+/// This is the load used by python's sortedcontainers.
+pub const DEFAULT_BLOCK_SIZE: usize = 1_000;
+
+/// Remembers in which block an element was inserted,
+/// to speed up following insertions of nearby elements.
+/// Obtained with `SortedList::insert_hint`.
+#[derive(Debug, Clone, Copy)]
+pub struct InsertHint(usize);
+
+/// Error returned when building a `SortedList` from a vector which is not sorted.
+/// The vector is given back untouched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotSortedError<T> {
+    /// First position whose element is smaller than the previous one.
+    pub index: usize,
+    /// The rejected vector.
+    pub data: Vec<T>,
+}
+
+impl<T> std::fmt::Display for NotSortedError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "elements are not sorted at index {}", self.index)
+    }
+}
+
+impl<T: std::fmt::Debug> std::error::Error for NotSortedError<T> {}
 
 /// Python's SortedList structure.
 /// A kind of flat BTree.
@@ -8,199 +60,4465 @@ use std::borrow::Borrow;
 pub struct SortedList<T> {
     data: Vec<Vec<T>>,
     block_size: usize,
+    len: usize,
 }
 
 impl<T: Ord> SortedList<T> {
-    /// Create a new `SortedList` with given block size.
-    pub fn new(block_size: usize) -> Self {
+    /// Create a new empty `SortedList` with a block size of `DEFAULT_BLOCK_SIZE`.
+    pub fn new() -> Self {
+        SortedList::with_block_size(DEFAULT_BLOCK_SIZE)
+    }
+
+    /// Create a new empty `SortedList` with given block size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is less than 2.
+    pub fn with_block_size(block_size: usize) -> Self {
+        assert_valid_block_size(block_size);
         SortedList {
             data: Vec::new(),
             block_size,
+            len: 0,
         }
     }
 
-    /// Iterate in order on all elements contained.
-    pub fn iter<'a>(&'a self) -> impl Iterator<Item = &'a T> + 'a {
-        self.data.iter().flatten()
+    /// Create a `SortedList` with given block size from an already sorted vector.
+    /// Elements are moved into full blocks without any search, in O(n).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is less than 2.
+    pub fn from_sorted_vec(block_size: usize, data: Vec<T>) -> Self {
+        debug_assert!(data.windows(2).all(|w| w[0] <= w[1]));
+        let mut list = SortedList::with_block_size(block_size);
+        list.extend_sorted_blocks(data.into_iter());
+        list
     }
 
-    /// Remove given element (any). Return true if it was here.
-    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    /// Like `from_sorted_vec` but check (in O(n)) that the data really is sorted,
+    /// giving it back otherwise.
+    pub fn try_from_sorted_vec(block_size: usize, data: Vec<T>) -> Result<Self, NotSortedError<T>> {
+        match data.windows(2).position(|w| w[0] > w[1]) {
+            Some(i) => Err(NotSortedError { index: i + 1, data }),
+            None => Ok(SortedList::from_sorted_vec(block_size, data)),
+        }
+    }
+
+    /// Return the number of elements contained.
+    /// This runs in O(1).
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if we contain no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the smallest element (if any) in O(1).
+    pub fn first(&self) -> Option<&T> {
+        self.data.first().and_then(|b| b.first())
+    }
+
+    /// Return the largest element (if any) in O(1).
+    pub fn last(&self) -> Option<&T> {
+        self.data.last().and_then(|b| b.last())
+    }
+
+    /// Return element at given position (in sorted order) if any.
+    /// This runs in O(number of blocks).
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.indexes_at(index)
+            .map(|(block_index, element_index)| &self.data[block_index][element_index])
+    }
+
+    /// Return the k-th largest element, `kth_largest(0)` being the maximum.
+    /// This runs in O(number of blocks), walking from the back.
+    pub fn kth_largest(&self, mut k: usize) -> Option<&T> {
+        for block in self.data.iter().rev() {
+            if k < block.len() {
+                return Some(&block[block.len() - 1 - k]);
+            }
+            k -= block.len();
+        }
+        None
+    }
+
+    /// Return the element at given quantile `q` in [0, 1] using the nearest-rank rule:
+    /// we return the element at position ceil(q * len) - 1 (the first one for q = 0).
+    /// Return `None` on empty lists or if `q` is not in [0, 1] (this includes NaN).
+    pub fn quantile(&self, q: f64) -> Option<&T> {
+        if self.is_empty() || !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+        let rank = (q * self.len as f64).ceil() as usize;
+        self.get(rank.max(1).min(self.len) - 1)
+    }
+
+    /// Return the median element (the lower one for even lengths).
+    pub fn median(&self) -> Option<&T> {
+        self.quantile(0.5)
+    }
+
+    /// Return how many elements are strictly smaller than given value.
+    /// This runs in O(log(n) + number of blocks).
+    pub fn rank<Q>(&self, value: &Q) -> usize
     where
         Q: Ord + ?Sized,
         T: Borrow<Q>,
     {
-        if let Some((block_index, element_index)) = self.indexes_for(value) {
-            self.data[block_index].remove(element_index);
-            let block_len = self.data[block_index].len();
-            if block_index > 0 && block_len < self.block_size / 2 {
-                // we are not big enough, we should fuse with previous block
-                // two cases: whether we end with one or two buffers.
-                let cumulated_size = self.data[block_index - 1].len() + block_len;
-                if cumulated_size <= self.block_size {
-                    // easy case, just append current block at end of previous one
-                    let to_redispatch = self.data.remove(block_index);
-                    self.data[block_index - 1].extend(to_redispatch);
-                } else {
-                    // hard case, we need to redispatch some of previous buffer's in us.
-                    let target_size = cumulated_size / 2;
-                    let moved_size = self.data[block_index - 1].len() - target_size;
-                    unsafe {
-                        // move data back at end of vector
-                        let buffer = &mut self.data[block_index][0] as *mut T;
-                        let end = buffer.offset(moved_size as isize);
-                        buffer.copy_to(end, block_len);
-                        self.data[block_index].set_len(block_len + moved_size);
-                        // move data from end of previous vector here
-                        let previous_data = &self.data[block_index - 1][target_size] as *const T;
-                        previous_data.copy_to_nonoverlapping(buffer, moved_size);
-                        self.data[block_index - 1].set_len(target_size);
-                    }
-                }
+        let (block_index, element_index) = self.lower_bound(value);
+        self.global_index(block_index, element_index)
+    }
+
+    /// Return how many elements are equal to given value.
+    /// This runs in O(log(n) + number of blocks spanned by the equal elements).
+    pub fn count<Q>(&self, value: &Q) -> usize
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let (block_index, mut element_index) = self.lower_bound(value);
+        let mut count = 0;
+        for block in self.data.iter().skip(block_index) {
+            let end = block.partition_point(|e| e.borrow() <= value);
+            count += end - element_index;
+            if end < block.len() {
+                break;
             }
-            true
-        } else {
-            false
+            element_index = 0;
         }
+        count
     }
 
-    fn block_index<Q>(&self, value: &Q) -> usize
+    /// Return the position at which given value should be inserted
+    /// to stay sorted, before any equal element.
+    pub fn bisect_left<Q>(&self, value: &Q) -> usize
     where
         Q: Ord + ?Sized,
         T: Borrow<Q>,
     {
-        // note : this code is copy pasted from the slice's binary search in standard library.
-        let mut size = self.data.len();
-        if size == 0 {
-            return 0;
-        }
-        let mut base = 0usize;
-        while size > 1 {
-            let half = size / 2;
-            let mid = base + half;
-            // mid is always in [0, size), that means mid is >= 0 and < size.
-            // mid >= 0: by definition
-            // mid < size: mid = size / 2 + size / 4 + size / 8 ...
-            let cmp = value
-                .cmp(unsafe { self.data[mid].get_unchecked(self.data[mid].len() - 1) }.borrow());
-            base = if cmp == std::cmp::Ordering::Greater {
-                mid
-            } else {
-                base
-            };
-            size -= half;
-        }
-        // base is always in [0, size) because base <= mid.
-        let cmp =
-            value.cmp(unsafe { self.data[base].get_unchecked(self.data[base].len() - 1) }.borrow());
-        if cmp == std::cmp::Ordering::Equal {
-            base
-        } else {
-            base + (cmp == std::cmp::Ordering::Greater) as usize
+        self.rank(value)
+    }
+
+    /// Return the position at which given value should be inserted
+    /// to stay sorted, after any equal element.
+    pub fn bisect_right<Q>(&self, value: &Q) -> usize
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let (block_index, element_index) = self.upper_bound(value);
+        self.global_index(block_index, element_index)
+    }
+
+    /// Return global position of the first element equal to given value
+    /// together with a reference to it.
+    pub fn get_full<Q>(&self, value: &Q) -> Option<(usize, &T)>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let (block_index, element_index) = self.lower_bound(value);
+        self.element_at(block_index, element_index)
+            .filter(|e| (*e).borrow() == value)
+            .map(|e| (self.global_index(block_index, element_index), e))
+    }
+
+    /// Binary search given value like `slice::binary_search` but with global positions.
+    /// Return `Ok` with the position of the first equal element if any
+    /// or `Err` with the position where the value could be inserted.
+    pub fn binary_search<Q>(&self, value: &Q) -> Result<usize, usize>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let (block_index, element_index) = self.lower_bound(value);
+        let position = self.global_index(block_index, element_index);
+        match self.element_at(block_index, element_index) {
+            Some(e) if e.borrow() == value => Ok(position),
+            _ => Err(position),
         }
     }
 
-    /// Return block index and index in block for given value.
-    fn indexes_for<Q>(&self, value: &Q) -> Option<(usize, usize)>
+    /// Return how many elements fall in given range of values.
+    /// This runs in O(log(n) + number of blocks) whatever the answer.
+    pub fn count_range<Q, R>(&self, range: R) -> usize
     where
         Q: Ord + ?Sized,
         T: Borrow<Q>,
+        R: RangeBounds<Q>,
     {
-        let block_index = self.block_index(value);
-        self.data
-            .get(block_index)
-            .and_then(|b| b.binary_search_by_key(&value, |t| t.borrow()).ok())
-            .map(|i| (block_index, i))
+        let (start_block, start_element) = self.start_position(range.start_bound());
+        let (end_block, end_element) = self.end_position(range.end_bound());
+        self.global_index(end_block, end_element)
+            .saturating_sub(self.global_index(start_block, start_element))
     }
 
-    /// Return if we contain given value.
-    /// This runs in O(log(n)) whatever the block size.
-    pub fn contains<Q>(&self, value: &Q) -> bool
+    /// Iterate in order on all elements in given range of values.
+    /// Only the blocks covered by the range are visited.
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, T>
     where
         Q: Ord + ?Sized,
         T: Borrow<Q>,
+        R: RangeBounds<Q>,
     {
-        let block_index = self.block_index(value);
-        self.data
-            .get(block_index)
-            .and_then(|b| b.binary_search_by_key(&value, |t| t.borrow()).ok())
-            .is_some()
+        let start = self.start_position(range.start_bound());
+        let end = self.end_position(range.end_bound());
+        Iter::between(self, start, end)
     }
 
-    /// Insert element at given position.
-    pub fn insert(&mut self, element: T) {
-        let mut target_block = self.block_index(&element);
-        if target_block == self.data.len() {
-            if target_block == 0 {
-                // first insert is a special case
-                let mut new_vec = Vec::with_capacity(self.block_size);
-                new_vec.push(element);
-                self.data.push(new_vec);
-                return;
+    /// Iterate in parallel on all elements, splitting work along blocks.
+    /// Collecting the elements keeps them in order.
+    #[cfg(feature = "rayon")]
+    pub fn par_iter(&self) -> impl ParallelIterator<Item = &T>
+    where
+        T: Sync,
+    {
+        self.data.par_iter().flat_map(|b| b.par_iter())
+    }
+
+    /// Iterate in order on all elements of both lists, without building a new list.
+    /// Duplicates are kept and on equal elements ours come first.
+    pub fn merge<'a>(&'a self, other: &'a SortedList<T>) -> Merge<'a, T> {
+        Merge::new(self, other)
+    }
+
+    /// Iterate in order on all pairs of consecutive elements.
+    pub fn adjacent_pairs(&self) -> impl Iterator<Item = (&T, &T)> {
+        self.iter().zip(self.iter().skip(1))
+    }
+
+    /// Iterate in order on the differences between consecutive elements.
+    pub fn gaps(&self) -> impl Iterator<Item = T::Output> + '_
+    where
+        T: Copy + std::ops::Sub,
+    {
+        self.adjacent_pairs().map(|(&a, &b)| b - a)
+    }
+
+    /// Iterate in order on the internal blocks, as slices.
+    /// Their concatenation is the sorted sequence of elements and none of them is empty,
+    /// but how elements are chunked is an implementation detail which may change.
+    pub fn blocks(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        self.data.iter().map(|b| b.as_slice())
+    }
+
+    /// Iterate on consecutive groups of `size` elements (the last one may be smaller),
+    /// regardless of block boundaries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn chunks(&self, size: usize) -> impl Iterator<Item = Iter<'_, T>> {
+        assert!(size != 0, "chunk size must be non-zero");
+        let mut start = (0, 0);
+        std::iter::from_fn(move || {
+            if start.0 == self.data.len() {
+                return None;
             }
-            target_block -= 1;
-        }
+            let (mut block_index, mut element_index) = start;
+            let mut remaining = size;
+            while block_index < self.data.len() {
+                let available = self.data[block_index].len() - element_index;
+                if remaining < available {
+                    element_index += remaining;
+                    break;
+                }
+                remaining -= available;
+                block_index += 1;
+                element_index = 0;
+            }
+            let chunk = Iter::between(self, start, (block_index, element_index));
+            start = (block_index, element_index);
+            Some(chunk)
+        })
+    }
 
-        if self.data[target_block].len() == self.block_size {
-            self.rebalance(target_block);
-            if *self.data[target_block].last().unwrap() <= element {
-                target_block += 1;
+    /// Iterate in order on elements at given range of positions (clamped to the length).
+    /// Blocks before the start are skipped using their lengths.
+    pub fn islice(&self, range: std::ops::Range<usize>) -> Iter<'_, T> {
+        let start = self.position_at(range.start);
+        let end = self.position_at(range.end);
+        Iter::between(self, start, end)
+    }
+
+    /// Return a cursor on the first element not smaller than given value
+    /// (or on the ghost position if there is none).
+    pub fn cursor_at<Q>(&self, value: &Q) -> Cursor<'_, T>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let (block_index, element_index) = self.lower_bound(value);
+        let index = self.global_index(block_index, element_index);
+        Cursor::new(self, (block_index, element_index), index)
+    }
+
+    /// Return a cursor on the element at given position
+    /// (or on the ghost position if out of bounds).
+    pub fn cursor_at_index(&self, index: usize) -> Cursor<'_, T> {
+        Cursor::new(self, self.position_at(index), index.min(self.len))
+    }
+
+    /// Return a mutable cursor on the first element not smaller than given value
+    /// (or on the ghost position if there is none).
+    pub fn cursor_mut_at<Q>(&mut self, value: &Q) -> CursorMut<'_, T>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let (block_index, element_index) = self.lower_bound(value);
+        let index = self.global_index(block_index, element_index);
+        CursorMut::new(self, (block_index, element_index), index)
+    }
+
+    /// Return a mutable cursor on the element at given position
+    /// (or on the ghost position if out of bounds).
+    pub fn cursor_mut_at_index(&mut self, index: usize) -> CursorMut<'_, T> {
+        let position = self.position_at(index);
+        let index = index.min(self.len);
+        CursorMut::new(self, position, index)
+    }
+
+    /// Iterate in order on all elements, with the possibility to jump forward
+    /// (see `SeekableIter::seek`).
+    pub fn seek_iter(&self) -> SeekableIter<'_, T> {
+        SeekableIter::new(self)
+    }
+
+    /// Iterate in order on the window of elements for which given comparator returns `Equal`.
+    /// The comparator must return `Less` for elements below the window and `Greater`
+    /// for elements above it, like for `slice::binary_search_by`.
+    pub fn range_by<F>(&self, mut f: F) -> Iter<'_, T>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        let start = self.partition_position(|e| f(e) == Ordering::Less);
+        let end = self.partition_position(|e| f(e) != Ordering::Greater);
+        Iter::between(self, start, end)
+    }
+
+    /// Iterate in order on all elements whose key (extracted by given function) is equal to `b`.
+    /// Keys must be sorted like the elements, like for `slice::binary_search_by_key`.
+    pub fn range_by_key<B, F>(&self, b: &B, mut f: F) -> Iter<'_, T>
+    where
+        B: Ord,
+        F: FnMut(&T) -> B,
+    {
+        self.range_by(|e| f(e).cmp(b))
+    }
+
+    /// Iterate in order on all elements not smaller than given value.
+    /// Only one search is done, at the start.
+    pub fn iter_from<Q>(&self, value: &Q) -> Iter<'_, T>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        Iter::between(self, self.lower_bound(value), (self.data.len(), 0))
+    }
+
+    /// Return the largest element not greater than given value.
+    pub fn floor<Q>(&self, value: &Q) -> Option<&T>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let (block_index, element_index) = self.upper_bound(value);
+        self.element_before(block_index, element_index)
+    }
+
+    /// Return the smallest element not smaller than given value.
+    pub fn ceiling<Q>(&self, value: &Q) -> Option<&T>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let (block_index, element_index) = self.lower_bound(value);
+        self.element_at(block_index, element_index)
+    }
+
+    /// Return the smallest element strictly greater than given value.
+    pub fn next_above<Q>(&self, value: &Q) -> Option<&T>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let (block_index, element_index) = self.upper_bound(value);
+        self.element_at(block_index, element_index)
+    }
+
+    /// Return the largest element strictly smaller than given value.
+    pub fn prev_below<Q>(&self, value: &Q) -> Option<&T>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let (block_index, element_index) = self.lower_bound(value);
+        self.element_before(block_index, element_index)
+    }
+
+    /// Return the element closest to given value according to given distance.
+    /// Only the floor and ceiling are considered so the distance needs to be consistent
+    /// with the order. On ties we prefer the smaller element.
+    pub fn nearest<Q, D, F>(&self, value: &Q, dist: F) -> Option<&T>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+        D: Ord,
+        F: Fn(&Q, &T) -> D,
+    {
+        match (self.floor(value), self.ceiling(value)) {
+            (Some(below), Some(above)) => {
+                if dist(value, above) < dist(value, below) {
+                    Some(above)
+                } else {
+                    Some(below)
+                }
             }
+            (below, above) => below.or(above),
         }
+    }
 
-        let block = &mut self.data[target_block];
-        let target_position = match block.binary_search(&element) {
-            Ok(i) => i,
-            Err(i) => i,
-        };
-        block.insert(target_position, element);
+    /// Return up to k elements strictly smaller and up to k elements strictly greater
+    /// than given value, both in order. The value does not need to be present.
+    pub fn neighbors<Q>(&self, value: &Q, k: usize) -> (Vec<&T>, Vec<&T>)
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let (block_index, element_index) = self.lower_bound(value);
+        let mut below: Vec<&T> = self
+            .iter_before_position(block_index, element_index)
+            .take(k)
+            .collect();
+        below.reverse();
+        let (block_index, element_index) = self.upper_bound(value);
+        let above = self
+            .iter_from_position(block_index, element_index)
+            .take(k)
+            .collect();
+        (below, above)
     }
 
-    fn rebalance(&mut self, block_index: usize) {
-        let mid = self.data[block_index].len() / 2;
-        let mut new_vec = Vec::with_capacity(self.block_size);
-        new_vec.extend(self.data[block_index].drain(mid..));
-        self.data.insert(block_index + 1, new_vec);
+    /// Pair each element of given ascending sequence of probes
+    /// with the largest element we contain which is not greater than it.
+    /// This runs in O(n + m) since we only move forward in our blocks.
+    pub fn asof_join<'a, I>(
+        &'a self,
+        probes: I,
+    ) -> impl Iterator<Item = (I::Item, Option<&'a T>)> + 'a
+    where
+        I: IntoIterator + 'a,
+        I::Item: Borrow<T>,
+    {
+        let mut block_index = 0;
+        let mut element_index = 0;
+        let mut floor = None;
+        probes.into_iter().map(move |probe| {
+            let target = probe.borrow();
+            while let Some(block) = self.data.get(block_index) {
+                if block[block.len() - 1] <= *target {
+                    // skip the whole block
+                    floor = block.last();
+                    block_index += 1;
+                    element_index = 0;
+                } else {
+                    element_index += block[element_index..].partition_point(|e| e <= target);
+                    if element_index > 0 {
+                        floor = Some(&block[element_index - 1]);
+                    }
+                    break;
+                }
+            }
+            (probe, floor)
+        })
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    #[test]
-    fn insert_range() {
-        let mut l = SortedList::new(1_000);
-        for x in 0..1_000_000 {
-            l.insert(x);
+    /// Return elements at all given positions (`None` when out of range) in given order.
+    /// Indices are resolved in a single pass over the blocks: O(number of blocks + k log(k)).
+    pub fn get_many(&self, indices: &[usize]) -> Vec<Option<&T>> {
+        let mut order: Vec<usize> = (0..indices.len()).collect();
+        order.sort_unstable_by_key(|&i| indices[i]);
+        let mut result = vec![None; indices.len()];
+        let mut blocks = self.data.iter();
+        let mut current_block = blocks.next();
+        let mut block_start = 0;
+        for i in order {
+            let index = indices[i];
+            while let Some(block) = current_block {
+                if index < block_start + block.len() {
+                    result[i] = Some(&block[index - block_start]);
+                    break;
+                }
+                block_start += block.len();
+                current_block = blocks.next();
+            }
         }
-        assert!(l.iter().cloned().eq(0..1_000_000));
+        result
     }
-    #[test]
-    fn insert_reversed_range() {
-        let mut l = SortedList::new(1_000);
-        for x in (0..1_000_000).rev() {
-            l.insert(x);
+
+    /// Return a uniformly chosen element (if any).
+    /// Each stored element (duplicates included) is equally likely.
+    #[cfg(feature = "rand")]
+    pub fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&T> {
+        if self.is_empty() {
+            None
+        } else {
+            self.get(rng.gen_range(0..self.len))
         }
-        assert!(l.iter().cloned().eq(0..1_000_000));
     }
-    #[test]
-    fn contains() {
-        let mut l = SortedList::new(1_000);
-        for x in (0..1_000_000).rev() {
-            l.insert(x);
-        }
-        assert!(l.contains(&500_000));
-        assert!(!l.contains(&1_000_000));
+
+    /// Iterate in order on all elements contained.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self)
     }
-    #[test]
-    fn remove() {
-        let mut l = SortedList::new(1_000);
-        for x in (0..1_000_000).rev() {
-            l.insert(x);
-        }
+
+    /// Remove given element (any). Return true if it was here.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        self.take(value).is_some()
+    }
+
+    /// Remove given element (any) and return it if it was here.
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        self.indexes_for(value)
+            .map(|(block_index, element_index)| self.remove_indexes(block_index, element_index))
+    }
+
+    /// Remove and return element at given position.
+    /// Panics if out of bounds.
+    pub fn remove_at(&mut self, index: usize) -> T {
+        let len = self.len;
+        self.try_remove_at(index)
+            .unwrap_or_else(|| panic!("removal index (is {}) should be < len (is {})", index, len))
+    }
+
+    /// Remove and return element at given position if any.
+    pub fn try_remove_at(&mut self, index: usize) -> Option<T> {
+        self.indexes_at(index)
+            .map(|(block_index, element_index)| self.remove_indexes(block_index, element_index))
+    }
+
+    /// Remove all elements equal to given value. Return how many were removed.
+    pub fn remove_all<Q>(&mut self, value: &Q) -> usize
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let start = self.lower_bound(value);
+        let end = self.upper_bound(value);
+        self.remove_between(start, end)
+    }
+
+    /// Remove all elements in given range of values. Return how many were removed.
+    /// Covered blocks are dropped as a whole and only the boundary blocks are cut.
+    pub fn remove_range<Q, R>(&mut self, range: R) -> usize
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+        R: RangeBounds<Q>,
+    {
+        let start = self.start_position(range.start_bound());
+        let end = self.end_position(range.end_bound());
+        self.remove_between(start, end)
+    }
+
+    /// Remove all elements between given (block index, index in block) positions
+    /// (end excluded), restoring invariants. Return how many were removed.
+    fn remove_between(&mut self, start: (usize, usize), end: (usize, usize)) -> usize {
+        let ((start_block, start_element), (end_block, end_element)) = (start, end);
+        if start >= end {
+            return 0;
+        }
+        let removed = if start_block == end_block {
+            self.data[start_block]
+                .drain(start_element..end_element)
+                .count()
+        } else {
+            let mut removed = self.data[start_block].len() - start_element;
+            self.data[start_block].truncate(start_element);
+            removed += self
+                .data
+                .drain(start_block + 1..end_block)
+                .map(|b| b.len())
+                .sum::<usize>();
+            if let Some(last_block) = self.data.get_mut(start_block + 1) {
+                removed += last_block.drain(..end_element).count();
+                self.fix_underflow(start_block + 1);
+            }
+            removed
+        };
+        self.len -= removed;
+        self.fix_underflow(start_block);
+        removed
+    }
+
+    /// Detach all elements between given (block index, index in block) positions
+    /// (end excluded), restoring invariants. Return the detached elements as blocks.
+    fn split_between(&mut self, start: (usize, usize), end: (usize, usize)) -> Vec<Vec<T>> {
+        let ((start_block, start_element), (end_block, end_element)) = (start, end);
+        if start >= end {
+            return Vec::new();
+        }
+        let removed = if start_block == end_block {
+            vec![self.data[start_block]
+                .drain(start_element..end_element)
+                .collect()]
+        } else {
+            let mut removed = Vec::with_capacity(end_block - start_block + 1);
+            removed.push(self.data[start_block].split_off(start_element));
+            removed.extend(self.data.drain(start_block + 1..end_block));
+            if let Some(last_block) = self.data.get_mut(start_block + 1) {
+                let kept = last_block.split_off(end_element);
+                removed.push(std::mem::replace(last_block, kept));
+                self.fix_underflow(start_block + 1);
+            }
+            removed
+        };
+        self.len -= removed.iter().map(|b| b.len()).sum::<usize>();
+        self.fix_underflow(start_block);
+        removed
+    }
+
+    /// Remove and return (in order) the n smallest elements (or all of them if we have less).
+    pub fn pop_first_n(&mut self, n: usize) -> Vec<T> {
+        let end = self.position_at(n);
+        flatten_blocks(self.split_between((0, 0), end))
+    }
+
+    /// Remove and return (in order) the n largest elements (or all of them if we have less).
+    pub fn pop_last_n(&mut self, n: usize) -> Vec<T> {
+        let start = self.position_at(self.len.saturating_sub(n));
+        flatten_blocks(self.split_between(start, (self.data.len(), 0)))
+    }
+
+    /// Modify element at given position with given function, moving it if needed.
+    /// Panics if out of bounds.
+    pub fn update_at<F: FnOnce(&mut T)>(&mut self, index: usize, f: F) {
+        let (block_index, element_index) = self.indexes_at(index).unwrap_or_else(|| {
+            panic!(
+                "update index (is {}) should be < len (is {})",
+                index, self.len
+            )
+        });
+        f(&mut self.data[block_index][element_index]);
+        let element = &self.data[block_index][element_index];
+        let still_sorted = self
+            .element_before(block_index, element_index)
+            .is_none_or(|previous| previous <= element)
+            && self
+                .iter_from_position(block_index, element_index + 1)
+                .next()
+                .is_none_or(|next| element <= next);
+        if !still_sorted {
+            let element = self.remove_indexes(block_index, element_index);
+            self.insert(element);
+        }
+    }
+
+    /// Remove element at given block index and index in block, restoring invariants.
+    fn remove_indexes(&mut self, block_index: usize, element_index: usize) -> T {
+        let element = self.data[block_index].remove(element_index);
+        self.len -= 1;
+        self.fix_underflow(block_index);
+        element
+    }
+
+    /// Remove and return the smallest element (if any).
+    pub fn pop_first(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            None
+        } else {
+            Some(self.remove_indexes(0, 0))
+        }
+    }
+
+    /// Remove and return the largest element (if any).
+    pub fn pop_last(&mut self) -> Option<T> {
+        let last_block = self.data.len().checked_sub(1)?;
+        let element = self.data[last_block].pop();
+        self.len -= 1;
+        self.fix_underflow(last_block);
+        element
+    }
+
+    /// Keep only elements satisfying given predicate.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        for block in &mut self.data {
+            block.retain(&mut f);
+        }
+        self.consolidate();
+    }
+
+    /// Remove all elements, keeping the allocated space for blocks.
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.len = 0;
+    }
+
+    /// Keep only the n smallest elements.
+    pub fn truncate(&mut self, n: usize) {
+        let start = self.position_at(n);
+        self.remove_between(start, (self.data.len(), 0));
+    }
+
+    /// Keep only the n largest elements.
+    pub fn keep_last(&mut self, n: usize) {
+        let end = self.position_at(self.len.saturating_sub(n));
+        self.remove_between((0, 0), end);
+    }
+
+    /// Move all elements (in order) into a single vector, without cloning them.
+    pub fn into_vec(self) -> Vec<T> {
+        flatten_blocks(self.data)
+    }
+
+    /// Move all elements not smaller than given value into a new list (with same block size).
+    /// Only the block containing the split point is cut, other blocks are moved as they are.
+    pub fn split_off<Q>(&mut self, value: &Q) -> SortedList<T>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let (block_index, element_index) = self.lower_bound(value);
+        self.split_off_position(block_index, element_index)
+    }
+
+    /// Move all elements at positions not smaller than given index into a new list
+    /// (with same block size). Panics if index is greater than the length.
+    pub fn split_off_at(&mut self, index: usize) -> SortedList<T> {
+        assert!(
+            index <= self.len,
+            "split index (is {}) should be <= len (is {})",
+            index,
+            self.len
+        );
+        let (block_index, element_index) = self.position_at(index);
+        self.split_off_position(block_index, element_index)
+    }
+
+    /// Move all elements starting at given block index and index in block into a new list.
+    fn split_off_position(&mut self, block_index: usize, element_index: usize) -> SortedList<T> {
+        let mut other = SortedList::with_block_size(self.block_size);
+        if block_index >= self.data.len() {
+            return other;
+        }
+        let moved_part = self.data[block_index].split_off(element_index);
+        other.data.reserve(self.data.len() - block_index);
+        other.data.push(moved_part);
+        other.data.extend(self.data.drain(block_index + 1..));
+        other.len = other.data.iter().map(|b| b.len()).sum();
+        self.len -= other.len;
+        other.fix_underflow(0);
+        self.fix_underflow(block_index);
+        other
+    }
+
+    /// Move all elements of other list into us.
+    /// When values do not overlap blocks are just moved, otherwise we merge in linear time.
+    pub fn append(&mut self, mut other: SortedList<T>) {
+        if other.is_empty() {
+            return;
+        }
+        let movable_blocks = other.block_size <= self.block_size;
+        if movable_blocks && (self.is_empty() || self.last() <= other.first()) {
+            self.len += other.len;
+            self.data.append(&mut other.data);
+        } else if movable_blocks && other.last() < self.first() {
+            other.data.append(&mut self.data);
+            self.data = other.data;
+            self.len += other.len;
+        } else {
+            let mut ours = std::mem::take(&mut self.data)
+                .into_iter()
+                .flatten()
+                .peekable();
+            let mut theirs = other.data.into_iter().flatten().peekable();
+            self.len = 0;
+            // on ties our elements come first
+            let merged = std::iter::from_fn(|| match (ours.peek(), theirs.peek()) {
+                (Some(a), Some(b)) if b < a => theirs.next(),
+                (Some(_), _) => ours.next(),
+                (None, _) => theirs.next(),
+            });
+            self.extend_sorted_blocks(merged);
+        }
+    }
+
+    /// Merge all given lists into a new one, with a block size picked from the total length.
+    /// This is a k-way merge with a binary heap over the lists' heads, filling output blocks
+    /// directly. On equal elements, those of earlier lists come first.
+    pub fn merge_all<I: IntoIterator<Item = SortedList<T>>>(lists: I) -> SortedList<T> {
+        use std::cmp::Reverse;
+        use std::collections::binary_heap::{BinaryHeap, PeekMut};
+        let mut total_len = 0;
+        let mut sources = Vec::new();
+        let mut heads = BinaryHeap::new();
+        for list in lists {
+            total_len += list.len;
+            let mut elements = list.into_iter();
+            if let Some(head) = elements.next() {
+                heads.push(Reverse((head, sources.len())));
+            }
+            sources.push(elements);
+        }
+        let merged = std::iter::from_fn(|| {
+            let mut top = heads.peek_mut()?;
+            let source = (top.0).1;
+            match sources[source].next() {
+                Some(next) => Some(std::mem::replace(&mut (top.0).0, next)),
+                None => Some((PeekMut::pop(top).0).0),
+            }
+        });
+        let mut list = SortedList::with_block_size(block_size_for(total_len));
+        list.extend_sorted_blocks(merged);
+        list
+    }
+
+    /// Add at our end all elements of given sorted iterator, filling blocks completely.
+    /// All elements must be not smaller than our last one.
+    fn extend_sorted_blocks<I: Iterator<Item = T>>(&mut self, mut elements: I) {
+        loop {
+            let needs_new_block = self.data.last().is_none_or(|b| b.len() >= self.block_size);
+            if needs_new_block {
+                match elements.next() {
+                    Some(element) => {
+                        let mut new_vec = Vec::with_capacity(self.block_size);
+                        new_vec.push(element);
+                        self.data.push(new_vec);
+                        self.len += 1;
+                    }
+                    None => return,
+                }
+            }
+            let last_block = self.data.last_mut().unwrap();
+            let before = last_block.len();
+            last_block.extend(elements.by_ref().take(self.block_size - before));
+            self.len += last_block.len() - before;
+            if last_block.len() < self.block_size {
+                return;
+            }
+        }
+    }
+
+    /// Return an iterator removing and yielding (in order) all elements.
+    /// The list is left empty (and reusable) even if the iterator is not consumed.
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain::new(self)
+    }
+
+    /// Remove all elements in given range of values and return an iterator yielding them in order.
+    /// Elements are removed right away, even if the iterator is not consumed,
+    /// and the list stays usable while the iterator is alive.
+    pub fn drain_range<Q, R>(&mut self, range: R) -> DrainRange<T>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+        R: RangeBounds<Q>,
+    {
+        let start = self.start_position(range.start_bound());
+        let end = self.end_position(range.end_bound());
+        DrainRange::new(self.split_between(start, end))
+    }
+
+    /// Remove all elements at positions in given range and return an iterator yielding them in order.
+    /// Unlike `Vec::drain` out of bounds ends are clamped to the length.
+    /// Elements are removed right away, even if the iterator is not consumed.
+    pub fn drain_islice(&mut self, range: std::ops::Range<usize>) -> DrainRange<T> {
+        let start = self.position_at(range.start);
+        let end = self.position_at(range.end);
+        DrainRange::new(self.split_between(start, end))
+    }
+
+    /// Remove one stored copy of each value of given ascending sequence.
+    /// A value repeated k times removes up to k copies.
+    /// Return how many elements were actually removed.
+    pub fn remove_sorted<Q, I>(&mut self, values: I) -> usize
+    where
+        Q: Ord,
+        T: Borrow<Q>,
+        I: IntoIterator<Item = Q>,
+    {
+        self.remove_sorted_by(values.into_iter(), |value, element| {
+            value.cmp(element.borrow())
+        })
+    }
+
+    /// Remove one stored copy of each element of other list (multiset difference):
+    /// an element stored k times here and j times in other is kept max(k - j, 0) times.
+    /// Return how many elements were removed.
+    pub fn difference_in_place(&mut self, other: &SortedList<T>) -> usize {
+        self.remove_sorted_by(other.iter(), |value, element| (*value).cmp(element))
+    }
+
+    /// Keep only elements also in other list (multiset intersection):
+    /// an element stored k times here and j times in other is kept min(k, j) times.
+    /// Blocks falling between two consecutive elements of other are dropped without looking inside.
+    pub fn intersect_in_place(&mut self, other: &SortedList<T>) {
+        let mut values = other.iter().peekable();
+        for block in &mut self.data {
+            match values.peek() {
+                Some(v) if *v <= &block[block.len() - 1] => block.retain(|e| {
+                    while values.next_if(|v| *v < e).is_some() {}
+                    values.next_if(|v| *v == e).is_some()
+                }),
+                _ => block.clear(),
+            }
+        }
+        self.consolidate();
+    }
+
+    /// Build a new list (with our block size) in one linear walk over both lists:
+    /// a value stored k times here and j times in other is stored `multiplicity(k, j)` times.
+    fn combine<F>(&self, other: &SortedList<T>, multiplicity: F) -> SortedList<T>
+    where
+        T: Clone,
+        F: Fn(usize, usize) -> usize,
+    {
+        fn run_length<T: Ord>(elements: &mut std::iter::Peekable<Iter<'_, T>>, value: &T) -> usize {
+            let mut length = 0;
+            while elements.next_if(|e| *e == value).is_some() {
+                length += 1;
+            }
+            length
+        }
+        let mut ours = self.iter().peekable();
+        let mut theirs = other.iter().peekable();
+        let mut list = SortedList::with_block_size(self.block_size);
+        loop {
+            let value = match (ours.peek(), theirs.peek()) {
+                (Some(a), Some(b)) => (*a).min(*b),
+                (Some(a), None) => *a,
+                (None, Some(b)) => *b,
+                (None, None) => return list,
+            };
+            let kept = multiplicity(run_length(&mut ours, value), run_length(&mut theirs, value));
+            list.extend_sorted_blocks(std::iter::repeat_n(value, kept).cloned());
+        }
+    }
+
+    /// Remove one stored copy of each value of given ascending sequence,
+    /// comparing values to our elements with given function.
+    /// Return how many elements were actually removed.
+    fn remove_sorted_by<V, I, C>(&mut self, values: I, compare: C) -> usize
+    where
+        I: Iterator<Item = V>,
+        C: Fn(&V, &T) -> Ordering,
+    {
+        let previous_len = self.len;
+        let mut values = values.peekable();
+        for block in &mut self.data {
+            match values.peek() {
+                None => break,
+                // nothing to remove here
+                Some(v) if compare(v, &block[block.len() - 1]) == Ordering::Greater => continue,
+                _ => (),
+            }
+            block.retain(|e| {
+                while values
+                    .next_if(|v| compare(v, e) == Ordering::Less)
+                    .is_some()
+                {}
+                values
+                    .next_if(|v| compare(v, e) == Ordering::Equal)
+                    .is_none()
+            });
+        }
+        self.consolidate();
+        previous_len - self.len
+    }
+
+    /// Keep only one element of each run of equal elements.
+    /// Return how many elements were removed.
+    pub fn dedup(&mut self) -> usize {
+        let previous_len = self.len;
+        let mut last_kept_block: Option<usize> = None;
+        for block_index in 0..self.data.len() {
+            self.data[block_index].dedup();
+            if let Some(kept_index) = last_kept_block {
+                // equal runs can continue across blocks
+                let (previous_blocks, next_blocks) = self.data.split_at_mut(block_index);
+                let last_kept = previous_blocks[kept_index].last().unwrap();
+                let duplicates = next_blocks[0].partition_point(|e| e == last_kept);
+                next_blocks[0].drain(..duplicates);
+            }
+            if !self.data[block_index].is_empty() {
+                last_kept_block = Some(block_index);
+            }
+        }
+        self.consolidate();
+        previous_len - self.len
+    }
+
+    /// Return an iterator removing and yielding (in order) all elements satisfying given predicate.
+    /// If the iterator is dropped before completion, remaining elements are kept.
+    pub fn extract_if<F: FnMut(&T) -> bool>(&mut self, f: F) -> ExtractIf<'_, T, F> {
+        ExtractIf::new(self, f)
+    }
+
+    /// Restore blocks invariants after arbitrary removals in any block:
+    /// drop empty blocks and fuse small blocks with their neighbours.
+    /// This also recomputes the length.
+    fn consolidate(&mut self) {
+        let mut blocks: Vec<Vec<T>> = Vec::with_capacity(self.data.len());
+        for block in self.data.drain(..).filter(|b| !b.is_empty()) {
+            match blocks.last_mut() {
+                Some(previous)
+                    if (previous.len() < self.block_size / 2
+                        || block.len() < self.block_size / 2)
+                        && previous.len() + block.len() <= self.block_size =>
+                {
+                    previous.extend(block)
+                }
+                _ => blocks.push(block),
+            }
+        }
+        self.len = blocks.iter().map(|b| b.len()).sum();
+        self.data = blocks;
+    }
+
+    /// Restore blocks invariants after some elements were removed from given block.
+    /// We never keep empty blocks and we try to fuse small blocks with their neighbours.
+    fn fix_underflow(&mut self, block_index: usize) {
+        let block_len = self.data[block_index].len();
+        if block_len == 0 {
+            // searches rely on all blocks having a last element
+            self.data.remove(block_index);
+        } else if block_index > 0 && block_len < self.block_size / 2 {
+            // we are not big enough, we should fuse with previous block
+            // two cases: whether we end with one or two buffers.
+            let cumulated_size = self.data[block_index - 1].len() + block_len;
+            if cumulated_size <= self.block_size {
+                // easy case, just append current block at end of previous one
+                let to_redispatch = self.data.remove(block_index);
+                self.data[block_index - 1].extend(to_redispatch);
+            } else {
+                // hard case, we need to redispatch some of previous buffer's in us.
+                let target_size = cumulated_size / 2;
+                let moved_size = self.data[block_index - 1].len() - target_size;
+                self.data[block_index].reserve(moved_size);
+                unsafe {
+                    // move data back at end of vector
+                    let buffer = &mut self.data[block_index][0] as *mut T;
+                    let end = buffer.add(moved_size);
+                    buffer.copy_to(end, block_len);
+                    self.data[block_index].set_len(block_len + moved_size);
+                    // move data from end of previous vector here
+                    let previous_data = &self.data[block_index - 1][target_size] as *const T;
+                    previous_data.copy_to_nonoverlapping(buffer, moved_size);
+                    self.data[block_index - 1].set_len(target_size);
+                }
+            }
+        } else if block_index == 0
+            && block_len < self.block_size / 2
+            && self.data.len() > 1
+            && block_len + self.data[1].len() <= self.block_size
+        {
+            // first block has no previous one, absorb the next one instead
+            let next_block = self.data.remove(1);
+            self.data[0].extend(next_block);
+        }
+    }
+
+    fn block_index<Q>(&self, value: &Q) -> usize
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        // note : this code is copy pasted from the slice's binary search in standard library.
+        let mut size = self.data.len();
+        if size == 0 {
+            return 0;
+        }
+        let mut base = 0usize;
+        while size > 1 {
+            let half = size / 2;
+            let mid = base + half;
+            // mid is always in [0, size), that means mid is >= 0 and < size.
+            // mid >= 0: by definition
+            // mid < size: mid = size / 2 + size / 4 + size / 8 ...
+            let cmp = value
+                .cmp(unsafe { self.data[mid].get_unchecked(self.data[mid].len() - 1) }.borrow());
+            base = if cmp == std::cmp::Ordering::Greater {
+                mid
+            } else {
+                base
+            };
+            size -= half;
+        }
+        // base is always in [0, size) because base <= mid.
+        let cmp =
+            value.cmp(unsafe { self.data[base].get_unchecked(self.data[base].len() - 1) }.borrow());
+        if cmp == std::cmp::Ordering::Equal {
+            base
+        } else {
+            base + (cmp == std::cmp::Ordering::Greater) as usize
+        }
+    }
+
+    /// Return block index and index in block for given value.
+    fn indexes_for<Q>(&self, value: &Q) -> Option<(usize, usize)>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let block_index = self.block_index(value);
+        self.data
+            .get(block_index)
+            .and_then(|b| b.binary_search_by_key(&value, |t| t.borrow()).ok())
+            .map(|i| (block_index, i))
+    }
+
+    /// Return block index and index in block of the first element not smaller than given value.
+    /// If there is none we return the number of blocks and 0.
+    fn lower_bound<Q>(&self, value: &Q) -> (usize, usize)
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let block_index = self.block_index(value);
+        let element_index = self
+            .data
+            .get(block_index)
+            .map_or(0, |b| b.partition_point(|e| e.borrow() < value));
+        (block_index, element_index)
+    }
+
+    /// Return block index and index in block of the first element greater than given value.
+    /// If there is none we return the number of blocks and 0.
+    fn upper_bound<Q>(&self, value: &Q) -> (usize, usize)
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let block_index = self
+            .data
+            .partition_point(|b| b[b.len() - 1].borrow() <= value);
+        let element_index = self
+            .data
+            .get(block_index)
+            .map_or(0, |b| b.partition_point(|e| e.borrow() <= value));
+        (block_index, element_index)
+    }
+
+    /// Return block index and index in block of the first element not satisfying
+    /// given predicate (which must hold for a prefix of the elements).
+    fn partition_position<P: FnMut(&T) -> bool>(&self, mut is_before: P) -> (usize, usize) {
+        let block_index = self.data.partition_point(|b| is_before(&b[b.len() - 1]));
+        let element_index = self
+            .data
+            .get(block_index)
+            .map_or(0, |b| b.partition_point(&mut is_before));
+        (block_index, element_index)
+    }
+
+    /// Return element at given block index and index in block.
+    fn element_at(&self, block_index: usize, element_index: usize) -> Option<&T> {
+        self.data
+            .get(block_index)
+            .and_then(|b| b.get(element_index))
+    }
+
+    /// Return element just before given block index and index in block.
+    fn element_before(&self, block_index: usize, element_index: usize) -> Option<&T> {
+        if element_index > 0 {
+            Some(&self.data[block_index][element_index - 1])
+        } else {
+            block_index
+                .checked_sub(1)
+                .and_then(|previous| self.data[previous].last())
+        }
+    }
+
+    /// Return block index and index in block of the first element after given start bound.
+    fn start_position<Q>(&self, bound: Bound<&Q>) -> (usize, usize)
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        match bound {
+            Bound::Included(value) => self.lower_bound(value),
+            Bound::Excluded(value) => self.upper_bound(value),
+            Bound::Unbounded => (0, 0),
+        }
+    }
+
+    /// Return block index and index in block of the first element after given end bound.
+    fn end_position<Q>(&self, bound: Bound<&Q>) -> (usize, usize)
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        match bound {
+            Bound::Included(value) => self.upper_bound(value),
+            Bound::Excluded(value) => self.lower_bound(value),
+            Bound::Unbounded => (self.data.len(), 0),
+        }
+    }
+
+    /// Iterate in order on all elements starting at given block index and index in block.
+    fn iter_from_position(
+        &self,
+        block_index: usize,
+        element_index: usize,
+    ) -> impl Iterator<Item = &T> {
+        let first_block = self
+            .data
+            .get(block_index)
+            .map_or(&[][..], |b| &b[element_index..]);
+        let next_blocks = self.data.get(block_index + 1..).unwrap_or(&[]);
+        first_block.iter().chain(next_blocks.iter().flatten())
+    }
+
+    /// Iterate in reverse order on all elements before given block index and index in block.
+    fn iter_before_position(
+        &self,
+        block_index: usize,
+        element_index: usize,
+    ) -> impl Iterator<Item = &T> {
+        let last_block = self
+            .data
+            .get(block_index)
+            .map_or(&[][..], |b| &b[..element_index]);
+        let previous_blocks = &self.data[..block_index.min(self.data.len())];
+        last_block
+            .iter()
+            .rev()
+            .chain(previous_blocks.iter().rev().flat_map(|b| b.iter().rev()))
+    }
+
+    /// Convert block index and index in block into a global position.
+    fn global_index(&self, block_index: usize, element_index: usize) -> usize {
+        self.data[..block_index]
+            .iter()
+            .map(|b| b.len())
+            .sum::<usize>()
+            + element_index
+    }
+
+    /// Return block index and index in block for given global position,
+    /// or the number of blocks and 0 when out of bounds.
+    fn position_at(&self, index: usize) -> (usize, usize) {
+        self.indexes_at(index).unwrap_or((self.data.len(), 0))
+    }
+
+    /// Return block index and index in block for given global position.
+    fn indexes_at(&self, mut index: usize) -> Option<(usize, usize)> {
+        if index >= self.len {
+            return None;
+        }
+        for (block_index, block) in self.data.iter().enumerate() {
+            if index < block.len() {
+                return Some((block_index, index));
+            }
+            index -= block.len();
+        }
+        None
+    }
+
+    /// Return if we contain given value.
+    /// This runs in O(log(n)) whatever the block size.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        self.find(value).is_some()
+    }
+
+    /// Return the stored element (any) equal to given value.
+    /// This is `BTreeSet::get` but `get` is already our positional access.
+    pub fn find<Q>(&self, value: &Q) -> Option<&T>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        self.indexes_for(value)
+            .map(|(block_index, element_index)| &self.data[block_index][element_index])
+    }
+
+    /// Test membership of all values in given ascending sequence of queries.
+    /// We move forward in the blocks and gallop inside them
+    /// which runs in O(n/B + k log(B)) instead of O(k log(n)).
+    /// Queries which are not ascending lead to wrong answers.
+    pub fn contains_sorted<'a, Q, I>(&self, queries: I) -> Vec<bool>
+    where
+        Q: Ord + ?Sized + 'a,
+        T: Borrow<Q>,
+        I: IntoIterator<Item = &'a Q>,
+    {
+        let mut block_index = 0;
+        let mut element_index = 0;
+        queries
+            .into_iter()
+            .map(|query| {
+                while let Some(block) = self.data.get(block_index) {
+                    if block[block.len() - 1].borrow() < query {
+                        block_index += 1;
+                        element_index = 0;
+                    } else {
+                        element_index += gallop(&block[element_index..], |e| e.borrow() < query);
+                        return block[element_index].borrow() == query;
+                    }
+                }
+                false
+            })
+            .collect()
+    }
+
+    /// Insert all elements of given sorted slice in a single sweep over our blocks.
+    pub fn insert_sorted_slice(&mut self, values: &[T])
+    where
+        T: Clone,
+    {
+        debug_assert!(values.windows(2).all(|w| w[0] <= w[1]));
+        self.insert_sorted_iter(values.iter().cloned())
+    }
+
+    /// Insert all elements of given sorted vector in a single sweep over our blocks.
+    pub fn insert_sorted_vec(&mut self, values: Vec<T>) {
+        debug_assert!(values.windows(2).all(|w| w[0] <= w[1]));
+        self.insert_sorted_iter(values.into_iter())
+    }
+
+    /// Insert all elements of given unsorted vector.
+    /// We sort it and merge it in a single sweep over our blocks
+    /// unless it has less elements than we have blocks, in which case we just insert them.
+    pub fn extend_batch(&mut self, mut batch: Vec<T>) {
+        if batch.len() < self.data.len() {
+            for element in batch {
+                self.insert(element);
+            }
+        } else {
+            batch.sort_unstable();
+            self.insert_sorted_iter(batch.into_iter());
+        }
+    }
+
+    /// Insert all elements of given sorted iterator in a single sweep over our blocks.
+    /// Blocks receiving no elements are just moved, others are merged and split if too big.
+    fn insert_sorted_iter<I: Iterator<Item = T>>(&mut self, values: I) {
+        let mut values = values.peekable();
+        let old_data = std::mem::take(&mut self.data);
+        let blocks_number = old_data.len();
+        self.data.reserve(blocks_number);
+        for (block_index, block) in old_data.into_iter().enumerate() {
+            let is_last_block = block_index + 1 == blocks_number;
+            let untouched = values
+                .peek()
+                .is_none_or(|v| !is_last_block && v > &block[block.len() - 1]);
+            if untouched {
+                self.data.push(block);
+                continue;
+            }
+            let mut merged = Vec::with_capacity(block.len() * 2);
+            let mut block = block.into_iter().peekable();
+            loop {
+                let take_value = match (block.peek(), values.peek()) {
+                    (Some(e), Some(v)) => v < e,
+                    (Some(_), None) => false,
+                    (None, Some(_)) => is_last_block,
+                    (None, None) => break,
+                };
+                if take_value {
+                    merged.push(values.next().unwrap());
+                    self.len += 1;
+                } else if let Some(e) = block.next() {
+                    merged.push(e);
+                } else {
+                    break;
+                }
+            }
+            self.push_chunked(merged);
+        }
+        // we might have had no blocks at all
+        self.extend_sorted_blocks(values);
+    }
+
+    /// Add given sorted block at our end, splitting it in balanced blocks if too big.
+    fn push_chunked(&mut self, block: Vec<T>) {
+        if block.len() <= self.block_size {
+            self.data.push(block);
+            return;
+        }
+        let chunks_number = block.len().div_ceil(self.block_size);
+        let chunk_size = block.len().div_ceil(chunks_number);
+        let mut elements = block.into_iter();
+        while elements.len() > 0 {
+            let mut chunk = Vec::with_capacity(self.block_size);
+            chunk.extend(elements.by_ref().take(chunk_size));
+            self.data.push(chunk);
+        }
+    }
+
+    /// Replace an element (any) equal to given one and return it.
+    /// If there is none we just insert the new element.
+    pub fn replace(&mut self, element: T) -> Option<T> {
+        if let Some((block_index, element_index)) = self.indexes_for(&element) {
+            Some(std::mem::replace(
+                &mut self.data[block_index][element_index],
+                element,
+            ))
+        } else {
+            self.insert(element);
+            None
+        }
+    }
+
+    /// Insert element at given position.
+    pub fn insert(&mut self, element: T) {
+        let target_block = self.block_index(&element);
+        self.insert_in_block(target_block, element);
+    }
+
+    /// Insert element and return the position at which it ended up.
+    pub fn insert_ranked(&mut self, element: T) -> usize {
+        let target_block = self.block_index(&element);
+        let (block_index, element_index) = self.insert_in_block(target_block, element);
+        self.global_index(block_index, element_index)
+    }
+
+    /// Insert element, first trying the block given by the hint (and the next one)
+    /// before falling back to a full search. Return a hint for the next insertion.
+    /// This is much faster for nearly sorted insertions.
+    pub fn insert_hint(&mut self, hint: Option<InsertHint>, element: T) -> InsertHint {
+        let target_block = hint
+            .and_then(|InsertHint(block_index)| {
+                self.hinted_block(block_index, &element)
+                    .or_else(|| self.hinted_block(block_index + 1, &element))
+            })
+            .unwrap_or_else(|| self.block_index(&element));
+        let (block_index, _) = self.insert_in_block(target_block, element);
+        InsertHint(block_index)
+    }
+
+    /// Return what `block_index` would return for given element,
+    /// if we can figure it out by looking only at given block.
+    fn hinted_block(&self, block_index: usize, element: &T) -> Option<usize> {
+        let block = self.data.get(block_index)?;
+        if block_index > 0 && self.data[block_index - 1].last()? >= element {
+            None
+        } else if element <= &block[block.len() - 1] {
+            Some(block_index)
+        } else if block_index + 1 == self.data.len() {
+            Some(self.data.len())
+        } else {
+            None
+        }
+    }
+
+    /// Insert element unless an equal one is already here,
+    /// in which case we give it back.
+    pub fn insert_unique(&mut self, element: T) -> Result<(), T> {
+        let target_block = self.block_index(&element);
+        // equal elements can only be in the first block whose last element is not smaller
+        if self
+            .data
+            .get(target_block)
+            .is_some_and(|b| b.binary_search(&element).is_ok())
+        {
+            Err(element)
+        } else {
+            self.insert_in_block(target_block, element);
+            Ok(())
+        }
+    }
+
+    /// Return the stored element equal to given key, inserting `make(key)` if there is none.
+    /// The made element must compare equal to the key.
+    pub fn get_or_insert_with<Q, F>(&mut self, key: &Q, make: F) -> &T
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+        F: FnOnce(&Q) -> T,
+    {
+        let target_block = self.block_index(key);
+        let found = self
+            .data
+            .get(target_block)
+            .and_then(|b| b.binary_search_by_key(&key, |t| t.borrow()).ok());
+        let (block_index, element_index) = match found {
+            Some(element_index) => (target_block, element_index),
+            None => {
+                let element = make(key);
+                debug_assert!(element.borrow() == key);
+                // we get the final position back since inserting can split the block
+                self.insert_in_block(target_block, element)
+            }
+        };
+        &self.data[block_index][element_index]
+    }
+
+    /// Look given value up and return an entry for it,
+    /// allowing to insert, get, replace or remove without searching again.
+    pub fn entry(&mut self, value: T) -> Entry<'_, T> {
+        let block_index = self.block_index(&value);
+        match self.data.get(block_index).map(|b| b.binary_search(&value)) {
+            Some(Ok(element_index)) => Entry::Occupied(OccupiedEntry {
+                list: self,
+                block_index,
+                element_index,
+            }),
+            Some(Err(element_index)) => Entry::Vacant(VacantEntry {
+                list: self,
+                value,
+                block_index,
+                element_index,
+            }),
+            None => Entry::Vacant(VacantEntry {
+                list: self,
+                value,
+                block_index,
+                element_index: 0,
+            }),
+        }
+    }
+
+    /// Insert element at given block index and index in block (which must be sorted positions),
+    /// splitting the block first if full.
+    /// Return the block index and index in block where the element ended up.
+    fn insert_at(
+        &mut self,
+        mut block_index: usize,
+        mut element_index: usize,
+        element: T,
+    ) -> (usize, usize) {
+        if block_index == self.data.len() {
+            if block_index == 0 {
+                self.insert_in_block(0, element);
+                return (0, 0);
+            }
+            block_index -= 1;
+            element_index = self.data[block_index].len();
+        }
+        self.len += 1;
+        if self.data[block_index].len() == self.block_size {
+            let mid = self.data[block_index].len() / 2;
+            self.rebalance(block_index);
+            if element_index > mid {
+                block_index += 1;
+                element_index -= mid;
+            }
+        }
+        self.data[block_index].insert(element_index, element);
+        (block_index, element_index)
+    }
+
+    /// Insert element in given block (obtained with `block_index`), splitting it if full.
+    /// Return the block index and index in block where the element ended up.
+    fn insert_in_block(&mut self, mut target_block: usize, element: T) -> (usize, usize) {
+        self.len += 1;
+        if target_block == self.data.len() {
+            if target_block == 0 {
+                // first insert is a special case
+                let mut new_vec = Vec::with_capacity(self.block_size);
+                new_vec.push(element);
+                self.data.push(new_vec);
+                return (0, 0);
+            }
+            target_block -= 1;
+        }
+
+        if self.data[target_block].len() == self.block_size {
+            // on a block boundary, prefer a neighbor with room over splitting
+            let block = &self.data[target_block];
+            if *block.last().unwrap() == element
+                && self
+                    .data
+                    .get(target_block + 1)
+                    .is_some_and(|b| b.len() < self.block_size)
+            {
+                target_block += 1;
+            } else if target_block > 0
+                && block[0] >= element
+                && self.data[target_block - 1].len() < self.block_size
+            {
+                target_block -= 1;
+            } else {
+                // an element equal to the last one of the left half goes left:
+                // rebalance never leaves it fuller than the right half
+                self.rebalance(target_block);
+                if *self.data[target_block].last().unwrap() < element {
+                    target_block += 1;
+                }
+            }
+        }
+
+        let block = &mut self.data[target_block];
+        let target_position = match block.binary_search(&element) {
+            Ok(i) => i,
+            Err(i) => i,
+        };
+        block.insert(target_position, element);
+        (target_block, target_position)
+    }
+
+    fn rebalance(&mut self, block_index: usize) {
+        let mid = self.data[block_index].len() / 2;
+        let mut new_vec = Vec::with_capacity(self.block_size);
+        new_vec.extend(self.data[block_index].drain(mid..));
+        self.data.insert(block_index + 1, new_vec);
+    }
+}
+
+/// Display elements in order like a slice.
+/// The alternate form also displays the blocks' lengths and bounds.
+impl<T: std::fmt::Debug> std::fmt::Debug for SortedList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        struct Elements<'a, T>(&'a [Vec<T>]);
+        impl<'a, T: std::fmt::Debug> std::fmt::Debug for Elements<'a, T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_list().entries(self.0.iter().flatten()).finish()
+            }
+        }
+        struct Block<'a, T>(&'a [T]);
+        impl<'a, T: std::fmt::Debug> std::fmt::Debug for Block<'a, T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("Block")
+                    .field("len", &self.0.len())
+                    .field("first", &self.0.first())
+                    .field("last", &self.0.last())
+                    .finish()
+            }
+        }
+        if f.alternate() {
+            f.debug_struct("SortedList")
+                .field("len", &self.len)
+                .field("block_size", &self.block_size)
+                .field("elements", &Elements(&self.data))
+                .field(
+                    "blocks",
+                    &self.data.iter().map(|b| Block(b)).collect::<Vec<_>>(),
+                )
+                .finish()
+        } else {
+            Elements(&self.data).fmt(f)
+        }
+    }
+}
+
+/// Lists are equal when they contain the same elements, whatever their blocks.
+impl<T: PartialEq> PartialEq for SortedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.data.iter().flatten().eq(other.data.iter().flatten())
+    }
+}
+
+impl<T: Eq> Eq for SortedList<T> {}
+
+/// Lexicographic order on elements, like for `Vec` and `BTreeSet`.
+impl<T: PartialOrd> PartialOrd for SortedList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.data
+            .iter()
+            .flatten()
+            .partial_cmp(other.data.iter().flatten())
+    }
+}
+
+impl<T: Ord> Ord for SortedList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.data.iter().flatten().cmp(other.data.iter().flatten())
+    }
+}
+
+impl<T: PartialEq> PartialEq<[T]> for SortedList<T> {
+    fn eq(&self, other: &[T]) -> bool {
+        self.len == other.len() && self.data.iter().flatten().eq(other.iter())
+    }
+}
+
+impl<T: PartialEq> PartialEq<&[T]> for SortedList<T> {
+    fn eq(&self, other: &&[T]) -> bool {
+        *self == **other
+    }
+}
+
+impl<T: PartialEq> PartialEq<Vec<T>> for SortedList<T> {
+    fn eq(&self, other: &Vec<T>) -> bool {
+        *self == **other
+    }
+}
+
+impl<T: PartialEq> PartialEq<SortedList<T>> for [T] {
+    fn eq(&self, other: &SortedList<T>) -> bool {
+        *other == *self
+    }
+}
+
+impl<T: PartialEq> PartialEq<SortedList<T>> for &[T] {
+    fn eq(&self, other: &SortedList<T>) -> bool {
+        *other == **self
+    }
+}
+
+impl<T: PartialEq> PartialEq<SortedList<T>> for Vec<T> {
+    fn eq(&self, other: &SortedList<T>) -> bool {
+        *other == **self
+    }
+}
+
+/// Hash the length then elements in order, so that equal lists have equal hashes
+/// whatever their blocks.
+impl<T: std::hash::Hash> std::hash::Hash for SortedList<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for element in self.data.iter().flatten() {
+            element.hash(state);
+        }
+    }
+}
+
+/// Clone keeping the same block layout.
+impl<T: Clone> Clone for SortedList<T> {
+    fn clone(&self) -> Self {
+        SortedList {
+            data: self
+                .data
+                .iter()
+                .map(|block| {
+                    let mut new_block = Vec::with_capacity(self.block_size);
+                    new_block.extend_from_slice(block);
+                    new_block
+                })
+                .collect(),
+            block_size: self.block_size,
+            len: self.len,
+        }
+    }
+
+    /// Reuse our existing blocks allocations.
+    fn clone_from(&mut self, source: &Self) {
+        self.data.truncate(source.data.len());
+        for (block, source_block) in self.data.iter_mut().zip(&source.data) {
+            block.clear();
+            block.extend_from_slice(source_block);
+        }
+        for source_block in &source.data[self.data.len()..] {
+            let mut new_block = Vec::with_capacity(source.block_size);
+            new_block.extend_from_slice(source_block);
+            self.data.push(new_block);
+        }
+        self.block_size = source.block_size;
+        self.len = source.len;
+    }
+}
+
+impl<T: Ord> Default for SortedList<T> {
+    /// Create an empty list with a block size of `DEFAULT_BLOCK_SIZE`.
+    fn default() -> Self {
+        SortedList::new()
+    }
+}
+
+/// Panic unless full blocks of given size can be split into two non-empty halves.
+fn assert_valid_block_size(block_size: usize) {
+    assert!(block_size >= 2, "block size must be at least 2");
+}
+
+/// Smallest block size we pick automatically.
+const MIN_BLOCK_SIZE: usize = 16;
+
+/// Block size we pick automatically for given number of elements: sqrt(n) (but not too small).
+fn block_size_for(len: usize) -> usize {
+    ((len as f64).sqrt().ceil() as usize).max(MIN_BLOCK_SIZE)
+}
+
+/// Sort and slice into blocks of size sqrt(n) (at least 16),
+/// which is much faster than inserting elements one by one.
+impl<T: Ord> From<Vec<T>> for SortedList<T> {
+    fn from(mut elements: Vec<T>) -> Self {
+        elements.sort_unstable();
+        SortedList::from_sorted_vec(block_size_for(elements.len()), elements)
+    }
+}
+
+/// Sort and slice into blocks like `From<Vec<T>>`.
+impl<T: Ord, const N: usize> From<[T; N]> for SortedList<T> {
+    fn from(array: [T; N]) -> Self {
+        SortedList::from(Vec::from(array))
+    }
+}
+
+/// Fill blocks of size sqrt(n) (at least 16) directly from the (ordered) set.
+impl<T: Ord> From<std::collections::BTreeSet<T>> for SortedList<T> {
+    fn from(set: std::collections::BTreeSet<T>) -> Self {
+        let mut list = SortedList::with_block_size(block_size_for(set.len()));
+        list.extend_sorted_blocks(set.into_iter());
+        list
+    }
+}
+
+/// Sort the heap with `into_sorted_vec` and slice into blocks of size sqrt(n) (at least 16).
+impl<T: Ord> From<std::collections::BinaryHeap<T>> for SortedList<T> {
+    fn from(heap: std::collections::BinaryHeap<T>) -> Self {
+        let elements = heap.into_sorted_vec();
+        SortedList::from_sorted_vec(block_size_for(elements.len()), elements)
+    }
+}
+
+/// Collect, then build like `From<Vec<T>>`.
+impl<T: Ord> std::iter::FromIterator<T> for SortedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        SortedList::from(iter.into_iter().collect::<Vec<T>>())
+    }
+}
+
+/// Collect and merge in batch (see `extend_batch`).
+impl<T: Ord> Extend<T> for SortedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.extend_batch(iter.into_iter().collect())
+    }
+}
+
+/// Collect and sort in parallel, then merge in a single sweep over our blocks.
+#[cfg(feature = "rayon")]
+impl<T: Ord + Send> ParallelExtend<T> for SortedList<T> {
+    fn par_extend<I: IntoParallelIterator<Item = T>>(&mut self, par_iter: I) {
+        let mut batch: Vec<T> = par_iter.into_par_iter().collect();
+        batch.par_sort_unstable();
+        self.insert_sorted_iter(batch.into_iter());
+    }
+}
+
+/// Draw a block size (between 2 and 64) then elements, inserted one by one
+/// to get realistic block layouts.
+#[cfg(feature = "arbitrary")]
+impl<'a, T: arbitrary::Arbitrary<'a> + Ord> arbitrary::Arbitrary<'a> for SortedList<T> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut list = SortedList::with_block_size(u.int_in_range(2..=64)?);
+        for element in u.arbitrary_iter()? {
+            list.insert(element?);
+        }
+        Ok(list)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        arbitrary::size_hint::and(<u8 as arbitrary::Arbitrary>::size_hint(depth), (0, None))
+    }
+}
+
+/// Draw a block size (between 2 and 64) and a vector of elements, inserted one by one.
+/// Shrinking removes and simplifies elements, the list being rebuilt each time.
+#[cfg(feature = "proptest")]
+impl<T: proptest::arbitrary::Arbitrary + Ord> proptest::arbitrary::Arbitrary for SortedList<T> {
+    type Parameters = (proptest::collection::SizeRange, T::Parameters);
+    type Strategy = proptest::strategy::Map<
+        (
+            std::ops::RangeInclusive<usize>,
+            proptest::collection::VecStrategy<T::Strategy>,
+        ),
+        fn((usize, Vec<T>)) -> Self,
+    >;
+
+    fn arbitrary_with((size, element): Self::Parameters) -> Self::Strategy {
+        use proptest::strategy::Strategy;
+        let build: fn((usize, Vec<T>)) -> Self = |(block_size, elements)| {
+            let mut list = SortedList::with_block_size(block_size);
+            for element in elements {
+                list.insert(element);
+            }
+            list
+        };
+        (
+            2..=64,
+            proptest::collection::vec(T::arbitrary_with(element), size),
+        )
+            .prop_map(build)
+    }
+}
+
+/// Multiset union: each value is kept as many times as in the list holding it most.
+/// This runs in O(n + m).
+impl<T: Ord + Clone> std::ops::BitOr<&SortedList<T>> for &SortedList<T> {
+    type Output = SortedList<T>;
+    fn bitor(self, other: &SortedList<T>) -> SortedList<T> {
+        self.combine(other, usize::max)
+    }
+}
+
+/// Multiset intersection: each value is kept as many times as in the list holding it least.
+/// This runs in O(n + m).
+impl<T: Ord + Clone> std::ops::BitAnd<&SortedList<T>> for &SortedList<T> {
+    type Output = SortedList<T>;
+    fn bitand(self, other: &SortedList<T>) -> SortedList<T> {
+        self.combine(other, usize::min)
+    }
+}
+
+/// Multiset difference: a value stored k times here and j times in other is kept
+/// max(k - j, 0) times. This runs in O(n + m).
+impl<T: Ord + Clone> std::ops::Sub<&SortedList<T>> for &SortedList<T> {
+    type Output = SortedList<T>;
+    fn sub(self, other: &SortedList<T>) -> SortedList<T> {
+        self.combine(other, usize::saturating_sub)
+    }
+}
+
+/// Multiset symmetric difference: a value stored k times here and j times in other
+/// is kept |k - j| times. This runs in O(n + m).
+impl<T: Ord + Clone> std::ops::BitXor<&SortedList<T>> for &SortedList<T> {
+    type Output = SortedList<T>;
+    fn bitxor(self, other: &SortedList<T>) -> SortedList<T> {
+        self.combine(other, usize::abs_diff)
+    }
+}
+
+impl<T: Ord> std::ops::Index<usize> for SortedList<T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &T {
+        self.get(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.len, index
+            )
+        })
+    }
+}
+
+/// Concatenate given blocks into a single vector.
+/// We reuse the allocation of the first block which is large enough to hold everything.
+fn flatten_blocks<T>(mut blocks: Vec<Vec<T>>) -> Vec<T> {
+    let total = blocks.iter().map(|b| b.len()).sum();
+    match blocks.iter().position(|b| b.capacity() >= total) {
+        Some(reused) => {
+            let mut elements = std::mem::take(&mut blocks[reused]);
+            let reused_len = elements.len();
+            let before: usize = blocks[..reused].iter().map(|b| b.len()).sum();
+            for block in blocks {
+                elements.extend(block);
+            }
+            // move the reused elements after the ones from previous blocks
+            elements[..reused_len + before].rotate_left(reused_len);
+            elements
+        }
+        None => {
+            let mut elements = Vec::with_capacity(total);
+            for block in blocks {
+                elements.extend(block);
+            }
+            elements
+        }
+    }
+}
+
+/// Return the partition point of given predicate in given slice
+/// (like `slice::partition_point`) by exponential search from the start.
+/// This runs in O(log(answer)).
+fn gallop<T, P: FnMut(&T) -> bool>(slice: &[T], mut is_before: P) -> usize {
+    let mut end = 1;
+    while end <= slice.len() && is_before(&slice[end - 1]) {
+        end *= 2;
+    }
+    let start = end / 2;
+    let end = end.min(slice.len());
+    start + slice[start..end].partition_point(is_before)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn check_invariants<T: Ord>(l: &SortedList<T>) {
+        assert!(l
+            .data
+            .iter()
+            .all(|b| !b.is_empty() && b.len() <= l.block_size));
+        assert!(l.iter().zip(l.iter().skip(1)).all(|(a, b)| a <= b));
+        assert_eq!(l.len(), l.iter().count());
+    }
+    /// List with given block size, filled by inserting elements one by one.
+    fn build<T: Ord, I: IntoIterator<Item = T>>(block_size: usize, elements: I) -> SortedList<T> {
+        let mut l = SortedList::with_block_size(block_size);
+        for x in elements {
+            l.insert(x);
+        }
+        l
+    }
+    /// Ordered on its key only, so that we can tell equal elements apart.
+    #[derive(Debug, Clone, Copy)]
+    struct Keyed<V>(u32, V);
+    impl<V> PartialEq for Keyed<V> {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+    impl<V> Eq for Keyed<V> {}
+    impl<V> PartialOrd for Keyed<V> {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl<V> Ord for Keyed<V> {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+    #[test]
+    fn insert_range() {
+        let mut l = SortedList::with_block_size(1_000);
+        for x in 0..1_000_000 {
+            l.insert(x);
+        }
+        assert!(l.iter().cloned().eq(0..1_000_000));
+    }
+    #[test]
+    fn insert_reversed_range() {
+        let mut l = SortedList::with_block_size(1_000);
+        for x in (0..1_000_000).rev() {
+            l.insert(x);
+        }
+        assert!(l.iter().cloned().eq(0..1_000_000));
+    }
+    #[test]
+    fn contains() {
+        let mut l = SortedList::with_block_size(1_000);
+        for x in (0..1_000_000).rev() {
+            l.insert(x);
+        }
+        assert!(l.contains(&500_000));
+        assert!(!l.contains(&1_000_000));
+    }
+    #[test]
+    fn remove() {
+        let mut l = SortedList::with_block_size(1_000);
+        for x in (0..1_000_000).rev() {
+            l.insert(x);
+        }
         for x in (0..1_000_000).filter(|&x| x % 7 == 0) {
             assert!(l.remove(&x));
         }
-        assert!(l.iter().cloned().eq((0..1_000_000).filter(|&x| x % 7 != 0)));
+        assert!(l.iter().cloned().eq((0..1_000_000).filter(|&x| x % 7 != 0)));
+    }
+    #[test]
+    fn len() {
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut l = SortedList::with_block_size(10);
+        let mut expected_len = 0;
+        for _ in 0..100_000 {
+            let x = rng.gen_range(0..1_000);
+            if rng.gen_bool(0.6) {
+                l.insert(x);
+                expected_len += 1;
+            } else if l.remove(&x) {
+                expected_len -= 1;
+            }
+            assert_eq!(l.len(), expected_len);
+        }
+        assert_eq!(l.iter().count(), expected_len);
+    }
+    #[test]
+    fn is_empty() {
+        let mut l = SortedList::with_block_size(1_000);
+        assert!(l.is_empty());
+        l.insert(3);
+        assert!(!l.is_empty());
+        assert!(l.remove(&3));
+        assert!(l.is_empty());
+        assert!(!l.contains(&3));
+        for x in 0..100_000 {
+            l.insert(x);
+        }
+        for x in (0..100_000).rev() {
+            assert!(l.remove(&x));
+        }
+        assert!(l.is_empty());
+        assert!(l.iter().next().is_none());
+        l.insert(5);
+        assert!(l.contains(&5));
+        assert!(!l.is_empty());
+    }
+    #[test]
+    fn first_last() {
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut l = SortedList::with_block_size(8);
+        assert_eq!(l.first(), None);
+        assert_eq!(l.last(), None);
+        for _ in 0..20_000 {
+            let x = rng.gen_range(0..500);
+            if rng.gen_bool(0.5) {
+                l.insert(x);
+            } else {
+                l.remove(&x);
+            }
+            assert_eq!(l.first(), l.iter().next());
+            assert_eq!(l.last(), l.iter().next_back());
+        }
+    }
+    #[test]
+    fn pop_first() {
+        let mut l = SortedList::with_block_size(1_000);
+        assert_eq!(l.pop_first(), None);
+        l.insert(1);
+        assert_eq!(l.pop_first(), Some(1));
+        assert!(l.is_empty());
+        assert_eq!(l.pop_first(), None);
+        for x in (0..100_000).rev() {
+            l.insert(x);
+        }
+        for x in 0..100_000 {
+            assert_eq!(l.pop_first(), Some(x));
+            assert_eq!(l.first(), l.iter().next());
+            assert_eq!(l.len(), 100_000 - x - 1);
+        }
+        assert_eq!(l.pop_first(), None);
+        l.insert(2);
+        assert!(l.contains(&2));
+    }
+    #[test]
+    fn pop_last() {
+        let mut l = SortedList::with_block_size(100);
+        assert_eq!(l.pop_last(), None);
+        for x in 0..10_000 {
+            l.insert(x);
+        }
+        let mut popped = Vec::new();
+        while let Some(x) = l.pop_last() {
+            assert_eq!(l.last(), l.iter().next_back());
+            popped.push(x);
+        }
+        assert!(popped.into_iter().eq((0..10_000).rev()));
+        assert!(l.is_empty());
+        l.insert(3);
+        l.insert(1);
+        assert!(l.contains(&1));
+        assert_eq!(l.pop_last(), Some(3));
+    }
+    #[test]
+    fn get() {
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut l = SortedList::with_block_size(16);
+        let mut v = Vec::new();
+        for _ in 0..5_000 {
+            let x = rng.gen_range(0..1_000);
+            l.insert(x);
+            v.push(x);
+        }
+        v.sort();
+        for (i, x) in v.iter().enumerate() {
+            assert_eq!(l.get(i), Some(x));
+        }
+        assert_eq!(l.get(v.len()), None);
+        for _ in 0..4_000 {
+            let x = rng.gen_range(0..1_000);
+            if l.remove(&x) {
+                let i = v.binary_search(&x).unwrap();
+                v.remove(i);
+            }
+        }
+        for (i, x) in v.iter().enumerate() {
+            assert_eq!(l.get(i), Some(x));
+        }
+        assert_eq!(l.get(v.len()), None);
+    }
+    #[test]
+    fn index() {
+        let mut l = SortedList::with_block_size(100);
+        for x in (0..10_000).rev() {
+            l.insert(x);
+        }
+        for x in (0..10_000).filter(|x| x % 3 == 0) {
+            l.remove(&x);
+        }
+        for (i, x) in (0..10_000).filter(|x| x % 3 != 0).enumerate() {
+            assert_eq!(l[i], x);
+        }
+    }
+    #[test]
+    #[should_panic(expected = "the len is 2 but the index is 2")]
+    fn index_out_of_bounds() {
+        let mut l = SortedList::with_block_size(100);
+        l.insert(1);
+        l.insert(2);
+        let _ = l[2];
+    }
+    #[test]
+    fn rank() {
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.rank(&3), 0);
+        // each even number 5 times, odd numbers are missing
+        for x in (0..200).filter(|x| x % 2 == 0) {
+            for _ in 0..5 {
+                l.insert(x);
+            }
+        }
+        for x in 0..202 {
+            let expected = l.iter().filter(|&&e| e < x).count();
+            assert_eq!(l.rank(&x), expected);
+        }
+        assert_eq!(l.rank(&-1), 0);
+        assert_eq!(l.rank(&1_000), l.len());
+    }
+    #[test]
+    fn count() {
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.count(&3), 0);
+        for x in 0..100 {
+            l.insert(x);
+        }
+        for _ in 0..35 {
+            l.insert(50);
+        }
+        assert_eq!(l.count(&50), 36);
+        assert_eq!(l.count(&49), 1);
+        assert_eq!(l.count(&51), 1);
+        assert_eq!(l.count(&100), 0);
+        for _ in 0..20 {
+            assert!(l.remove(&50));
+        }
+        assert_eq!(l.count(&50), 16);
+        assert_eq!(l.count(&50), l.iter().filter(|&&e| e == 50).count());
+    }
+    #[test]
+    fn bisect() {
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.bisect_left(&4), 0);
+        assert_eq!(l.bisect_right(&4), 0);
+        for _ in 0..2_000 {
+            l.insert(rng.gen_range(0..50) * 2);
+        }
+        for x in -1..102 {
+            assert_eq!(l.bisect_left(&x), l.iter().filter(|&&e| e < x).count());
+            assert_eq!(l.bisect_right(&x), l.iter().filter(|&&e| e <= x).count());
+            assert_eq!(l.bisect_right(&x) - l.bisect_left(&x), l.count(&x));
+        }
+    }
+    #[test]
+    fn asof_join() {
+        let mut l = SortedList::with_block_size(10);
+        for x in (0..1_000).map(|x| x * 10) {
+            l.insert(x);
+        }
+        let probes = vec![-5, 0, 3, 10, 15, 15, 99, 100, 5_000, 9_990, 20_000];
+        let joined: Vec<_> = l.asof_join(probes.iter().copied()).collect();
+        let expected: Vec<_> = probes
+            .iter()
+            .map(|&p| (p, l.iter().rfind(|&&e| e <= p)))
+            .collect();
+        assert_eq!(joined, expected);
+        assert_eq!(joined[0], (-5, None));
+        assert_eq!(joined[3], (10, Some(&10)));
+        assert_eq!(joined[10], (20_000, Some(&9_990)));
+        let empty = SortedList::with_block_size(10);
+        assert!(empty.asof_join(vec![1, 2]).eq(vec![(1, None), (2, None)]));
+    }
+    #[test]
+    fn floor_ceiling() {
+        use rand::prelude::*;
+        use std::collections::BTreeSet;
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut l = SortedList::with_block_size(10);
+        let mut t = BTreeSet::new();
+        assert_eq!(l.floor(&3), None);
+        assert_eq!(l.ceiling(&3), None);
+        for _ in 0..1_000 {
+            let x = rng.gen_range(0..5_000);
+            l.insert(x);
+            t.insert(x);
+        }
+        for x in -1..5_001 {
+            assert_eq!(l.floor(&x), t.range(..=x).next_back());
+            assert_eq!(l.ceiling(&x), t.range(x..).next());
+        }
+    }
+    #[test]
+    fn next_above_prev_below() {
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.next_above(&3), None);
+        assert_eq!(l.prev_below(&3), None);
+        for x in 0..20 {
+            l.insert(x);
+        }
+        // 10 spans several blocks
+        for _ in 0..50 {
+            l.insert(10);
+        }
+        assert_eq!(l.next_above(&10), Some(&11));
+        assert_eq!(l.prev_below(&10), Some(&9));
+        assert_eq!(l.next_above(&19), None);
+        assert_eq!(l.prev_below(&0), None);
+        assert_eq!(l.next_above(&-1), Some(&0));
+        assert_eq!(l.prev_below(&100), Some(&19));
+        for x in 0..19 {
+            assert_eq!(l.next_above(&x), Some(&(x + 1)));
+            assert_eq!(l.prev_below(&(x + 1)), Some(&x));
+        }
+    }
+    #[test]
+    fn get_full() {
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.get_full(&1), None);
+        for _ in 0..1_000 {
+            l.insert(rng.gen_range(0..100) * 2);
+        }
+        let v: Vec<i32> = l.iter().cloned().collect();
+        for x in -1..201 {
+            let expected = v.iter().position(|&e| e == x).map(|i| (i, &v[i]));
+            assert_eq!(l.get_full(&x), expected);
+        }
+    }
+    #[test]
+    fn find() {
+        let mut l = SortedList::with_block_size(4);
+        for (key, name) in [
+            (3, "three"),
+            (1, "one"),
+            (7, "seven"),
+            (5, "five"),
+            (9, "nine"),
+        ]
+        .iter()
+        {
+            l.insert(Keyed(*key, *name));
+        }
+        assert_eq!(l.find(&Keyed(7, "")).map(|k| k.1), Some("seven"));
+        assert_eq!(l.find(&Keyed(1, "")).map(|k| k.1), Some("one"));
+        assert!(l.find(&Keyed(4, "four")).is_none());
+        assert!(l.contains(&Keyed(9, "")));
+        assert!(!l.contains(&Keyed(2, "")));
+    }
+    #[test]
+    fn kth_largest() {
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.kth_largest(0), None);
+        for x in 0..1_000 {
+            l.insert(x);
+        }
+        for x in (0..1_000).filter(|x| x % 3 == 0 || *x > 990) {
+            l.remove(&x);
+        }
+        let mut v: Vec<_> = l.iter().cloned().collect();
+        v.reverse();
+        for (k, x) in v.iter().enumerate() {
+            assert_eq!(l.kth_largest(k), Some(x));
+        }
+        assert_eq!(l.kth_largest(v.len()), None);
+    }
+    #[test]
+    fn quantile() {
+        let mut l = SortedList::with_block_size(2);
+        assert_eq!(l.quantile(0.5), None);
+        assert_eq!(l.median(), None);
+        for x in [35, 20, 50, 15, 40].iter() {
+            l.insert(*x);
+        }
+        // numpy.percentile(data, q, method="inverted_cdf")
+        assert_eq!(l.quantile(0.0), Some(&15));
+        assert_eq!(l.quantile(0.05), Some(&15));
+        assert_eq!(l.quantile(0.3), Some(&20));
+        assert_eq!(l.quantile(0.4), Some(&20));
+        assert_eq!(l.quantile(0.5), Some(&35));
+        assert_eq!(l.quantile(0.95), Some(&50));
+        assert_eq!(l.quantile(1.0), Some(&50));
+        assert_eq!(l.median(), Some(&35));
+        assert_eq!(l.quantile(-0.1), None);
+        assert_eq!(l.quantile(1.1), None);
+        assert_eq!(l.quantile(f64::NAN), None);
+        let mut l = SortedList::with_block_size(10);
+        for x in 1..=100 {
+            l.insert(x);
+        }
+        assert_eq!(l.quantile(0.5), Some(&50));
+        assert_eq!(l.quantile(0.95), Some(&95));
+        assert_eq!(l.quantile(0.99), Some(&99));
+        assert_eq!(l.quantile(0.991), Some(&100));
+    }
+    #[test]
+    fn get_many() {
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.get_many(&[0, 3]), vec![None, None]);
+        for x in (0..1_000).rev() {
+            l.insert(x * 2);
+        }
+        let indices = [999, 5, 1_000, 0, 5, 10_000, 500, 11];
+        let expected: Vec<_> = indices.iter().map(|&i| l.get(i)).collect();
+        assert_eq!(l.get_many(&indices), expected);
+        assert_eq!(l.get_many(&[]), Vec::<Option<&i32>>::new());
+    }
+    #[test]
+    fn binary_search() {
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.binary_search(&3), Err(0));
+        for x in 0..100 {
+            l.insert(x * 2);
+            l.insert(x * 2);
+        }
+        let v: Vec<_> = l.iter().cloned().collect();
+        for x in -1..201 {
+            let expected = match v.binary_search(&x) {
+                Ok(_) => Ok(v.iter().position(|&e| e == x).unwrap()),
+                Err(i) => Err(i),
+            };
+            assert_eq!(l.binary_search(&x), expected);
+        }
+        let block_max = l.data[1].last().cloned().unwrap();
+        assert_eq!(l.binary_search(&block_max), Ok(l.rank(&block_max)));
+        assert_eq!(l.binary_search(&-5), Err(0));
+        assert_eq!(l.binary_search(&500), Err(200));
+    }
+    #[test]
+    fn contains_sorted() {
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.contains_sorted(&[1, 2]), vec![false, false]);
+        for x in 0..1_000 {
+            l.insert(x * 3);
+        }
+        let queries: Vec<i32> = vec![-4, 0, 0, 1, 3, 3, 299, 300, 2_997, 2_997, 2_998, 5_000];
+        let expected: Vec<bool> = queries.iter().map(|q| l.contains(q)).collect();
+        assert_eq!(l.contains_sorted(&queries), expected);
+        let all: Vec<i32> = (-10..3_010).collect();
+        let expected: Vec<bool> = all.iter().map(|q| l.contains(q)).collect();
+        assert_eq!(l.contains_sorted(&all), expected);
+    }
+    #[cfg(feature = "rand")]
+    #[test]
+    fn choose() {
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(6);
+        let mut l = SortedList::with_block_size(2);
+        assert_eq!(l.choose(&mut rng), None);
+        for x in [3, 1, 2, 2].iter() {
+            l.insert(*x);
+        }
+        let mut counts = [0; 4];
+        for _ in 0..40_000 {
+            counts[*l.choose(&mut rng).unwrap()] += 1;
+        }
+        assert_eq!(counts[0], 0);
+        assert!((9_000..11_000).contains(&counts[1]));
+        assert!((19_000..21_000).contains(&counts[2]));
+        assert!((9_000..11_000).contains(&counts[3]));
+    }
+    #[test]
+    fn count_range() {
+        use std::ops::Bound::*;
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.count_range(..), 0);
+        for x in 0..500 {
+            l.insert(x / 2);
+        }
+        let v: Vec<i32> = l.iter().cloned().collect();
+        let count = |f: &dyn Fn(i32) -> bool| v.iter().filter(|&&e| f(e)).count();
+        for a in -2..252 {
+            for b in (a - 3)..(a + 30) {
+                assert_eq!(l.count_range(a..b), count(&|e| a <= e && e < b));
+                assert_eq!(l.count_range(a..=b), count(&|e| a <= e && e <= b));
+                assert_eq!(
+                    l.count_range((Excluded(a), Included(b))),
+                    count(&|e| a < e && e <= b)
+                );
+            }
+            assert_eq!(l.count_range(a..), count(&|e| a <= e));
+            assert_eq!(l.count_range(..a), count(&|e| e < a));
+        }
+        assert_eq!(l.count_range(..), 500);
+        assert_eq!(l.count_range(10..10), 0);
+        assert_eq!(l.count_range((Excluded(10), Excluded(10))), 0);
+    }
+    #[test]
+    fn neighbors() {
+        let mut l = SortedList::with_block_size(4);
+        assert_eq!(l.neighbors(&3, 2), (vec![], vec![]));
+        for x in 0..50 {
+            l.insert(x * 2);
+        }
+        for _ in 0..10 {
+            l.insert(40);
+        }
+        let (below, above) = l.neighbors(&40, 5);
+        assert_eq!(below, vec![&30, &32, &34, &36, &38]);
+        assert_eq!(above, vec![&42, &44, &46, &48, &50]);
+        let (below, above) = l.neighbors(&41, 2);
+        assert_eq!(below, vec![&40, &40]);
+        assert_eq!(above, vec![&42, &44]);
+        let (below, above) = l.neighbors(&2, 3);
+        assert_eq!(below, vec![&0]);
+        assert_eq!(above, vec![&4, &6, &8]);
+        let (below, above) = l.neighbors(&97, 3);
+        assert_eq!(below, vec![&92, &94, &96]);
+        assert_eq!(above, vec![&98]);
+        assert_eq!(l.neighbors(&200, 1), (vec![&98], vec![]));
+        assert_eq!(l.neighbors(&-1, 1), (vec![], vec![&0]));
+    }
+    #[test]
+    fn nearest() {
+        let dist = |a: &i32, b: &i32| (a - b).abs();
+        let mut l = SortedList::with_block_size(4);
+        assert_eq!(l.nearest(&3, dist), None);
+        for x in 0..50 {
+            l.insert(x * 10);
+        }
+        assert_eq!(l.nearest(&-100, dist), Some(&0));
+        assert_eq!(l.nearest(&1_000, dist), Some(&490));
+        assert_eq!(l.nearest(&20, dist), Some(&20));
+        assert_eq!(l.nearest(&24, dist), Some(&20));
+        assert_eq!(l.nearest(&26, dist), Some(&30));
+        // ties prefer the smaller element
+        assert_eq!(l.nearest(&25, dist), Some(&20));
+    }
+    #[test]
+    fn remove_at() {
+        let mut l = SortedList::with_block_size(10);
+        let mut v = Vec::new();
+        for x in 0..1_000 {
+            l.insert(x);
+            v.push(x);
+        }
+        assert_eq!(l.remove_at(0), v.remove(0));
+        assert_eq!(l.remove_at(l.len() - 1), v.pop().unwrap());
+        let boundary = l.data[0].len();
+        assert_eq!(l.remove_at(boundary), v.remove(boundary));
+        assert_eq!(l.remove_at(boundary - 1), v.remove(boundary - 1));
+        while l.len() > 3 {
+            let i = l.len() / 3;
+            assert_eq!(l.remove_at(i), v.remove(i));
+            assert!(l.iter().eq(v.iter()));
+        }
+        assert_eq!(l.try_remove_at(3), None);
+        assert!(l.iter().eq(v.iter()));
+    }
+    #[test]
+    #[should_panic(expected = "removal index (is 1) should be < len (is 1)")]
+    fn remove_at_out_of_bounds() {
+        let mut l = SortedList::with_block_size(10);
+        l.insert(1);
+        l.remove_at(1);
+    }
+    #[test]
+    fn remove_all() {
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.remove_all(&3), 0);
+        for x in 0..100 {
+            l.insert(x);
+        }
+        for _ in 0..35 {
+            l.insert(50);
+        }
+        for _ in 0..3 {
+            l.insert(7);
+        }
+        assert_eq!(l.remove_all(&50), 36);
+        assert_eq!(l.remove_all(&7), 4);
+        assert_eq!(l.remove_all(&0), 1);
+        assert_eq!(l.remove_all(&99), 1);
+        assert_eq!(l.remove_all(&50), 0);
+        assert_eq!(l.len(), 96);
+        assert!(l.data.iter().all(|b| !b.is_empty()));
+        assert!(l.iter().cloned().eq((1..99).filter(|&x| x != 50 && x != 7)));
+        let mut l = SortedList::with_block_size(10);
+        for _ in 0..100 {
+            l.insert(1);
+        }
+        assert_eq!(l.remove_all(&1), 100);
+        assert!(l.is_empty());
+        assert!(l.data.is_empty());
+    }
+    #[test]
+    fn take() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+        #[derive(PartialEq, Eq, PartialOrd, Ord)]
+        struct Counted(String, Rc<Cell<usize>>);
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.1.set(self.1.get() + 1);
+            }
+        }
+        let drops = Rc::new(Cell::new(0));
+        let mut l = SortedList::with_block_size(4);
+        for x in 0..100 {
+            l.insert(Counted(format!("{:03}", x), drops.clone()));
+        }
+        let probe = Counted("042".to_owned(), drops.clone());
+        let taken = l.take(&probe).unwrap();
+        assert_eq!(taken.0, "042");
+        assert!(l.take(&probe).is_none());
+        assert_eq!(drops.get(), 0);
+        drop(taken);
+        drop(probe);
+        assert_eq!(drops.get(), 2);
+        drop(l);
+        assert_eq!(drops.get(), 101);
+    }
+    #[test]
+    fn replace() {
+        let mut l = SortedList::with_block_size(4);
+        for x in 0..20 {
+            assert!(l.replace(Keyed(x, 0)).is_none());
+        }
+        assert_eq!(l.replace(Keyed(7, 1)).map(|k| k.1), Some(0));
+        assert_eq!(l.replace(Keyed(7, 2)).map(|k| k.1), Some(1));
+        assert_eq!(l.len(), 20);
+        assert!(l.iter().map(|k| k.0).eq(0..20));
+        assert!(l
+            .iter()
+            .map(|k| k.1)
+            .eq((0..20).map(|x| if x == 7 { 2 } else { 0 })));
+    }
+    #[test]
+    fn insert_unique() {
+        let mut l = SortedList::with_block_size(4);
+        for x in 0..20 {
+            assert_eq!(l.insert_unique(x), Ok(()));
+        }
+        for x in 0..20 {
+            assert_eq!(l.insert_unique(x), Err(x));
+        }
+        // test last element of a block and first element of the next one
+        let last = *l.data[1].last().unwrap();
+        let first = l.data[2][0];
+        assert_eq!(l.insert_unique(last), Err(last));
+        assert_eq!(l.insert_unique(first), Err(first));
+        assert_eq!(l.insert_unique(-1), Ok(()));
+        assert_eq!(l.insert_unique(20), Ok(()));
+        assert_eq!(l.len(), 22);
+        assert!(l.iter().cloned().eq(-1..21));
+    }
+    #[test]
+    fn get_or_insert_with() {
+        let mut l: SortedList<Box<str>> = SortedList::with_block_size(4);
+        let mut made = 0;
+        for word in "the quick brown fox jumps over the lazy dog the end".split(' ') {
+            let stored = l.get_or_insert_with(word, |w| {
+                made += 1;
+                w.into()
+            });
+            assert_eq!(&**stored, word);
+        }
+        assert_eq!(made, 9);
+        assert_eq!(l.len(), 9);
+        // inserting in full blocks must return the final location
+        let mut l = SortedList::with_block_size(4);
+        for x in (0..100).map(|x| x * 2) {
+            assert_eq!(*l.get_or_insert_with(&x, |x| *x), x);
+        }
+        for x in 0..200 {
+            assert_eq!(*l.get_or_insert_with(&x, |x| *x), x);
+        }
+        assert!(l.iter().cloned().eq(0..200));
+    }
+    #[test]
+    fn retain() {
+        let mut l = SortedList::with_block_size(10);
+        for x in 0..1_000 {
+            l.insert(x);
+        }
+        l.retain(|_| true);
+        assert!(l.iter().cloned().eq(0..1_000));
+        // empty interior blocks
+        l.retain(|&x| !(100..900).contains(&x));
+        assert_eq!(l.len(), 200);
+        assert!(l.data.iter().all(|b| !b.is_empty()));
+        assert!(l.iter().cloned().eq((0..100).chain(900..1_000)));
+        l.retain(|&x| x % 2 == 0);
+        assert!(l
+            .iter()
+            .cloned()
+            .eq((0..100).chain(900..1_000).filter(|x| x % 2 == 0)));
+        l.insert(501);
+        assert!(l.contains(&501));
+        assert!(!l.contains(&500));
+        l.retain(|_| false);
+        assert!(l.is_empty());
+        assert!(l.data.is_empty());
+        l.insert(3);
+        assert!(l.contains(&3));
+    }
+    #[test]
+    fn extract_if() {
+        let mut l = SortedList::with_block_size(10);
+        for x in (0..1_000).rev() {
+            l.insert(x % 500);
+        }
+        let extracted: Vec<_> = l.extract_if(|x| x % 3 == 0).collect();
+        let mut all: Vec<_> = extracted.iter().chain(l.iter()).cloned().collect();
+        all.sort();
+        assert!(all.into_iter().eq((0..1_000).map(|x| x / 2)));
+        assert!(extracted
+            .iter()
+            .cloned()
+            .eq((0..1_000).map(|x| x / 2).filter(|x| x % 3 == 0)));
+        assert!(l
+            .iter()
+            .cloned()
+            .eq((0..1_000).map(|x| x / 2).filter(|x| x % 3 != 0)));
+        assert_eq!(l.len(), 1_000 - extracted.len());
+        // drop in the middle
+        let mut l = SortedList::with_block_size(10);
+        for x in 0..1_000 {
+            l.insert(x);
+        }
+        let mut extractor = l.extract_if(|x| x % 2 == 0 || *x < 300);
+        assert!(extractor
+            .by_ref()
+            .take(400)
+            .eq((0..300).chain((300..500).filter(|x| x % 2 == 0))));
+        drop(extractor);
+        assert_eq!(l.len(), 600);
+        assert!(l.data.iter().all(|b| !b.is_empty()));
+        assert!(l
+            .iter()
+            .cloned()
+            .eq((300..500).filter(|x| x % 2 != 0).chain(500..1_000)));
+        l.insert(0);
+        assert!(l.contains(&0));
+        assert_eq!(l.first(), Some(&0));
+        // leaking in the middle of a block leaves an empty but valid list
+        let mut extractor = l.extract_if(|x| x % 2 == 0);
+        assert_eq!(extractor.next(), Some(0));
+        std::mem::forget(extractor);
+        check_invariants(&l);
+        assert!(l.is_empty() && !l.contains(&501));
+        l.insert(3);
+        assert_eq!(l.first(), Some(&3));
+        // a panicking predicate keeps the elements it did not extract
+        let mut l: SortedList<u32> = (0..100).collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            l.extract_if(|x| if *x == 50 { panic!() } else { x % 2 == 0 })
+                .for_each(drop)
+        }));
+        assert!(result.is_err());
+        check_invariants(&l);
+        assert!(l.iter().copied().eq((1..50).step_by(2).chain(50..100)));
+    }
+    #[test]
+    fn drain() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+        #[derive(PartialEq, Eq, PartialOrd, Ord)]
+        struct Counted(u32, Rc<Cell<usize>>);
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.1.set(self.1.get() + 1);
+            }
+        }
+        let drops = Rc::new(Cell::new(0));
+        let mut l = SortedList::with_block_size(10);
+        for x in (0..1_000).rev() {
+            l.insert(Counted(x, drops.clone()));
+        }
+        assert!(l.drain().map(|c| c.0).eq(0..1_000));
+        assert_eq!(drops.get(), 1_000);
+        assert!(l.is_empty());
+        for x in 0..1_000 {
+            l.insert(Counted(x, drops.clone()));
+        }
+        let mut drain = l.drain();
+        assert_eq!(drain.size_hint(), (1_000, Some(1_000)));
+        assert!(drain.by_ref().take(15).map(|c| c.0).eq(0..15));
+        assert_eq!(drops.get(), 1_015);
+        drop(drain);
+        assert_eq!(drops.get(), 2_000);
+        assert!(l.is_empty());
+        assert!(l.iter().next().is_none());
+        l.insert(Counted(7, drops.clone()));
+        assert_eq!(l.len(), 1);
+        drop(l);
+        assert_eq!(drops.get(), 2_001);
+    }
+    #[test]
+    fn drain_range() {
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.drain_range(..).count(), 0);
+        for x in 0..1_000 {
+            l.insert(x);
+        }
+        // inside one block
+        assert!(l.drain_range(3..5).eq(3..5));
+        assert!(l.drain_range(3..5).eq(None::<i32>));
+        // across many blocks
+        assert!(l.drain_range(100..=800).eq(100..=800));
+        assert_eq!(l.len(), 1_000 - 2 - 701);
+        assert!(l.data.iter().all(|b| !b.is_empty()));
+        assert!(l.iter().cloned().eq((0..3).chain(5..100).chain(801..1_000)));
+        // dropping without consuming still removes
+        drop(l.drain_range(..50));
+        assert!(l.iter().cloned().eq((50..100).chain(801..1_000)));
+        let drained = l.drain_range(990..);
+        assert_eq!(drained.size_hint(), (10, Some(10)));
+        assert!(drained.eq(990..1_000));
+        assert_eq!(
+            l.drain_range((Bound::Included(20), Bound::Excluded(10)))
+                .count(),
+            0
+        );
+        l.insert(500);
+        assert!(l.contains(&500));
+        assert!(l
+            .iter()
+            .cloned()
+            .eq((50..100).chain(Some(500)).chain(801..990)));
+        assert!(l
+            .drain_range(..)
+            .eq((50..100).chain(Some(500)).chain(801..990)));
+        assert!(l.is_empty());
+        // the iterator owns the removed elements: the list is usable meanwhile
+        l.extend(0..100);
+        let drained = l.drain_range(10..20);
+        l.insert(15);
+        check_invariants(&l);
+        assert!(drained.eq(10..20));
+        assert_eq!(l.len(), 91);
+    }
+    #[test]
+    fn drain_islice() {
+        let mut l = SortedList::with_block_size(10);
+        let mut v = Vec::new();
+        for x in 0..1_000 {
+            l.insert(x);
+            v.push(x);
+        }
+        assert!(l.drain_islice(100..200).eq(v.drain(100..200)));
+        assert!(l.drain_islice(5..7).eq(v.drain(5..7)));
+        let (start, end) = (7, 5);
+        assert!(l.drain_islice(start..end).eq(None::<i32>));
+        assert!(l.iter().eq(v.iter()));
+        // the end is clamped
+        assert!(l.drain_islice(500..10_000).eq(v.drain(500..)));
+        assert!(l.drain_islice(600..700).eq(None::<i32>));
+        assert!(l.data.iter().all(|b| !b.is_empty()));
+        assert!(l.iter().eq(v.iter()));
+        assert_eq!(l.len(), v.len());
+        assert!(l.drain_islice(0..usize::MAX).eq(v.drain(..)));
+        assert!(l.is_empty());
+    }
+    #[test]
+    fn clear() {
+        let mut l = SortedList::with_block_size(10);
+        for x in 0..1_000 {
+            l.insert(x);
+        }
+        let capacity = l.data.capacity();
+        l.clear();
+        assert!(l.is_empty());
+        assert!(l.iter().next().is_none());
+        assert!(!l.contains(&5));
+        assert_eq!(l.data.capacity(), capacity);
+        for x in (0..500).rev() {
+            l.insert(-x * 3);
+        }
+        assert_eq!(l.len(), 500);
+        assert!(l.contains(&-3));
+        assert!(!l.contains(&5));
+        assert!(l.iter().cloned().eq((0..500).rev().map(|x| -x * 3)));
+    }
+    #[test]
+    fn truncate() {
+        let mut l = SortedList::with_block_size(10);
+        for x in 0..1_000 {
+            l.insert(x);
+        }
+        l.truncate(2_000);
+        assert_eq!(l.len(), 1_000);
+        l.truncate(999);
+        assert!(l.iter().cloned().eq(0..999));
+        let boundary = l.data[0].len() + l.data[1].len();
+        l.truncate(boundary);
+        assert_eq!(l.data.len(), 2);
+        assert!(l.iter().cloned().eq(0..boundary as i32));
+        l.truncate(3);
+        assert!(l.iter().cloned().eq(0..3));
+        l.truncate(0);
+        assert!(l.is_empty());
+        assert!(l.data.is_empty());
+        l.insert(1);
+        assert!(l.contains(&1));
+    }
+    #[test]
+    fn keep_last() {
+        let mut l = SortedList::with_block_size(64);
+        for x in 0..10_000 {
+            l.insert(x);
+            l.keep_last(1_000);
+            assert_eq!(l.len(), (x + 1).min(1_000) as usize);
+            assert_eq!(l.first(), Some(&(x - 999).max(0)));
+        }
+        assert!(l.iter().cloned().eq(9_000..10_000));
+        assert!(l.data.iter().all(|b| !b.is_empty()));
+        l.keep_last(2_000);
+        assert_eq!(l.len(), 1_000);
+        l.keep_last(0);
+        assert!(l.is_empty());
+    }
+    #[test]
+    fn split_off() {
+        let mut l = SortedList::with_block_size(10);
+        for x in 0..1_000 {
+            l.insert(x);
+        }
+        let mut right = l.split_off(&500);
+        assert!(l.iter().cloned().eq(0..500));
+        assert!(right.iter().cloned().eq(500..1_000));
+        assert_eq!((l.len(), right.len()), (500, 500));
+        // split exactly on the first element of a block
+        let first = right.data[3][0];
+        let far_right = right.split_off(&first);
+        assert!(right.iter().cloned().eq(500..first));
+        assert!(far_right.iter().cloned().eq(first..1_000));
+        assert_eq!(far_right.data[0][0], first);
+        for list in [&l, &right, &far_right].iter() {
+            assert!(list.data.iter().all(|b| !b.is_empty() && b.len() <= 10));
+            assert_eq!(list.len(), list.iter().count());
+        }
+        assert!(l.split_off(&5_000).is_empty());
+        let everything = l.split_off(&-5);
+        assert!(l.is_empty());
+        assert!(everything.iter().cloned().eq(0..500));
+        l.insert(3);
+        right.insert(3);
+        assert!(l.contains(&3));
+        assert_eq!(right.first(), Some(&3));
+    }
+    #[test]
+    fn split_off_at() {
+        let mut l = SortedList::with_block_size(10);
+        for x in 0..1_000 {
+            l.insert(x / 3);
+        }
+        let original: Vec<_> = l.iter().cloned().collect();
+        let right = l.split_off_at(400);
+        assert_eq!((l.len(), right.len()), (400, 600));
+        assert!(l.iter().chain(right.iter()).eq(original.iter()));
+        let boundary = l.data[0].len();
+        let middle = l.split_off_at(boundary);
+        assert!(l
+            .iter()
+            .chain(middle.iter())
+            .chain(right.iter())
+            .eq(original.iter()));
+        assert!(l.split_off_at(l.len()).is_empty());
+        let everything = l.split_off_at(0);
+        assert!(l.is_empty());
+        assert_eq!(everything.len(), boundary);
+    }
+    #[test]
+    #[should_panic(expected = "split index (is 2) should be <= len (is 1)")]
+    fn split_off_at_out_of_bounds() {
+        let mut l = SortedList::with_block_size(10);
+        l.insert(1);
+        l.split_off_at(2);
+    }
+    #[test]
+    fn append() {
+        // disjoint ranges
+        let mut l = build(10, 0..100);
+        l.append(build(10, 100..200));
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(0..200));
+        l.append(build(8, -100..0));
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(-100..200));
+        // interleaved ranges
+        let mut l = build(10, (0..1_000).step_by(2));
+        l.append(build(10, (1..1_000).step_by(2)));
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(0..1_000));
+        // different block sizes
+        let mut l = build(10, 0..100);
+        l.append(build(100, 100..1_000));
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(0..1_000));
+        let mut l = build(10, (0..1_000).step_by(3));
+        l.append(build(1_000, 0..1_000));
+        check_invariants(&l);
+        let mut expected: Vec<_> = (0..1_000).chain((0..1_000).step_by(3)).collect();
+        expected.sort();
+        assert!(l.iter().eq(expected.iter()));
+        let mut l = SortedList::with_block_size(10);
+        l.append(build(10, 0..100));
+        l.append(SortedList::with_block_size(10));
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(0..100));
+        l.insert(50);
+        l.remove(&20);
+        check_invariants(&l);
+    }
+    #[test]
+    fn remove_range() {
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.remove_range(..), 0);
+        for x in 0..1_000 {
+            l.insert(x);
+        }
+        assert_eq!(l.remove_range(3..5), 2);
+        assert_eq!(l.remove_range(100..=800), 701);
+        assert_eq!(l.remove_range(100..=800), 0);
+        assert_eq!(l.remove_range((Bound::Excluded(990), Bound::Unbounded)), 9);
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq((0..3).chain(5..100).chain(801..991)));
+        assert_eq!(l.remove_range(..), 288);
+        assert!(l.is_empty());
+    }
+    #[test]
+    fn pop_first_n() {
+        use rand::prelude::*;
+        use std::collections::BTreeSet;
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut l = SortedList::with_block_size(100);
+        let mut t = BTreeSet::new();
+        for _ in 0..9_500 {
+            let x = rng.gen::<u32>();
+            if t.insert(x) {
+                l.insert(x);
+            }
+        }
+        for _ in 0..10 {
+            let expected: Vec<_> = (0..1_000).filter_map(|_| t.pop_first()).collect();
+            assert_eq!(l.pop_first_n(1_000), expected);
+            check_invariants(&l);
+        }
+        assert!(l.is_empty());
+        assert!(l.pop_first_n(3).is_empty());
+    }
+    #[test]
+    fn pop_last_n() {
+        let mut l = SortedList::with_block_size(10);
+        for x in 0..1_000 {
+            l.insert(x);
+        }
+        assert!(l.pop_last_n(0).is_empty());
+        assert!(l.pop_last_n(5).into_iter().eq(995..1_000));
+        // exactly empty the last block
+        let last_block_len = l.data.last().unwrap().len();
+        let blocks = l.data.len();
+        let end = 995 - last_block_len as i32;
+        assert!(l.pop_last_n(last_block_len).into_iter().eq(end..995));
+        assert_eq!(l.data.len(), blocks - 1);
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(0..end));
+        assert!(l.pop_last_n(10_000).into_iter().eq(0..end));
+        assert!(l.is_empty());
+    }
+    #[test]
+    fn dedup() {
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.dedup(), 0);
+        for x in 0..100 {
+            l.insert(x);
+            l.insert(x / 2);
+        }
+        // a run over three blocks
+        for _ in 0..25 {
+            l.insert(60);
+        }
+        assert_eq!(l.dedup(), 125);
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(0..100));
+        assert_eq!(l.dedup(), 0);
+        let mut l = SortedList::with_block_size(10);
+        for _ in 0..100 {
+            l.insert(1);
+        }
+        assert_eq!(l.dedup(), 99);
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(Some(1)));
+    }
+    #[test]
+    fn remove_sorted() {
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.remove_sorted(vec![1, 2]), 0);
+        for x in 0..1_000 {
+            l.insert(x / 2);
+        }
+        // 3 appears twice in the list and three times in the input: two removals
+        let removed = l.remove_sorted(vec![-5, 3, 3, 3, 10, 11, 11, 250, 499, 700]);
+        assert_eq!(removed, 2 + 1 + 2 + 1 + 1);
+        check_invariants(&l);
+        let mut expected: Vec<_> = (0..1_000).map(|x| x / 2).collect();
+        for x in [3, 3, 10, 11, 11, 250, 499].iter() {
+            let i = expected.binary_search(x).unwrap();
+            expected.remove(i);
+        }
+        assert!(l.iter().eq(expected.iter()));
+        let everything: Vec<_> = expected.clone();
+        assert_eq!(l.remove_sorted(everything), expected.len());
+        assert!(l.is_empty());
+    }
+    #[test]
+    fn insert_sorted() {
+        let mut l = SortedList::with_block_size(10);
+        l.insert_sorted_slice(&[1, 5, 9]);
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(vec![1, 5, 9]));
+        let mut l = SortedList::with_block_size(10);
+        for x in (0..1_000).map(|x| x * 3) {
+            l.insert(x);
+        }
+        let batch: Vec<i32> = (-50..3_050).step_by(2).collect();
+        l.insert_sorted_slice(&batch);
+        check_invariants(&l);
+        let mut expected: Vec<_> = (0..1_000)
+            .map(|x| x * 3)
+            .chain(batch.iter().cloned())
+            .collect();
+        expected.sort();
+        assert!(l.iter().eq(expected.iter()));
+        // few values only touch a few blocks
+        l.insert_sorted_vec(vec![7, 7, 7, 2_000]);
+        expected.extend(vec![7, 7, 7, 2_000]);
+        expected.sort();
+        check_invariants(&l);
+        assert!(l.iter().eq(expected.iter()));
+        l.insert_sorted_vec(Vec::new());
+        assert_eq!(l.len(), expected.len());
+    }
+    #[test]
+    fn entry() {
+        let mut l = SortedList::with_block_size(4);
+        l.insert_sorted_vec((0..20).map(|x| x * 2).collect());
+        for x in (0..20).map(|x| x * 2) {
+            assert_eq!(*l.entry(x).or_insert(), x);
+        }
+        assert_eq!(l.len(), 20);
+        // blocks are full: vacant insertions need to split them
+        assert!(l.data.iter().all(|b| b.len() == 4));
+        for x in (0..20).map(|x| x * 2 + 1) {
+            match l.entry(x) {
+                Entry::Vacant(entry) => {
+                    assert_eq!(*entry.get(), x);
+                    assert_eq!(*entry.insert(), x)
+                }
+                Entry::Occupied(_) => panic!("{} is not here", x),
+            }
+            check_invariants(&l);
+        }
+        assert!(l.iter().cloned().eq(0..40));
+        match l.entry(10) {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(*entry.get(), 10);
+                assert_eq!(entry.replace(10), 10);
+                assert_eq!(entry.remove(), 10);
+            }
+            Entry::Vacant(_) => panic!("10 is here"),
+        }
+        assert!(!l.contains(&10));
+        assert_eq!(*l.entry(10).or_insert(), 10);
+        assert_eq!(*l.entry(10).or_insert(), 10);
+        assert_eq!(*l.entry(100).or_insert(), 100);
+        assert_eq!(*l.entry(-1).or_insert(), -1);
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq((-1..40).chain(Some(100))));
+    }
+    #[test]
+    fn insert_ranked() {
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(8);
+        let mut l = SortedList::with_block_size(8);
+        for _ in 0..2_000 {
+            let x = rng.gen_range(0..300);
+            let rank = l.insert_ranked(x);
+            assert_eq!(l.get(rank), Some(&x));
+            assert!(l.rank(&x) <= rank && rank < l.bisect_right(&x));
+        }
+        check_invariants(&l);
+    }
+    #[test]
+    fn insert_hint() {
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut l = SortedList::with_block_size(10);
+        let mut hint = None;
+        for x in 0..1_000 {
+            hint = Some(l.insert_hint(hint, x));
+        }
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(0..1_000));
+        // nearly sorted
+        let mut v = Vec::new();
+        for x in 0..1_000 {
+            let y = x + rng.gen_range(0..30);
+            hint = Some(l.insert_hint(hint, y));
+            v.push(y);
+        }
+        check_invariants(&l);
+        // wrong hints
+        for x in 0..1_000 {
+            let y = rng.gen_range(-100..1_100);
+            let wrong = InsertHint(rng.gen_range(0..1_000));
+            l.insert_hint(Some(wrong), y);
+            l.insert_hint(Some(InsertHint(x)), y);
+            v.push(y);
+            v.push(y);
+        }
+        check_invariants(&l);
+        v.extend(0..1_000);
+        v.sort();
+        assert!(l.iter().eq(v.iter()));
+    }
+    #[test]
+    fn update_at() {
+        let mut l = SortedList::with_block_size(10);
+        for x in 0..100 {
+            l.insert((x * 10, x));
+        }
+        // stays in place
+        l.update_at(5, |e| e.0 += 5);
+        assert_eq!(l[5], (55, 5));
+        // moves inside the block
+        l.update_at(1, |e| e.0 = 31);
+        assert_eq!(l[3], (31, 1));
+        // moves to another block
+        l.update_at(2, |e| e.0 = 905);
+        assert_eq!(l[90], (905, 3));
+        l.update_at(99, |e| e.0 = -1);
+        assert_eq!(l[0], (-1, 99));
+        check_invariants(&l);
+        assert_eq!(l.len(), 100);
+    }
+    #[test]
+    fn extend_batch() {
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(10);
+        let mut l = SortedList::with_block_size(10);
+        let mut v = Vec::new();
+        for size in [0, 3, 100, 1_000, 5].iter() {
+            let batch: Vec<u32> = (0..*size).map(|_| rng.gen_range(0..500)).collect();
+            v.extend(batch.iter().cloned());
+            l.extend_batch(batch);
+            check_invariants(&l);
+        }
+        v.sort();
+        assert!(l.iter().eq(v.iter()));
+    }
+    #[test]
+    fn difference_in_place() {
+        let mut l = build(4, [1, 2, 2, 2, 3, 5, 8, 8, 13]);
+        assert_eq!(
+            l.difference_in_place(&build(4, [0, 2, 2, 4, 8, 8, 8, 13, 21])),
+            5
+        );
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(vec![1, 2, 3, 5]));
+        assert_eq!(l.difference_in_place(&SortedList::with_block_size(4)), 0);
+        assert_eq!(l.difference_in_place(&build(4, [-1, 1, 2, 3, 5, 7])), 4);
+        assert!(l.is_empty());
+        let mut l = build(4, 0..1_000);
+        let odd = build(4, (0..1_000).filter(|x| x % 2 == 1));
+        assert_eq!(l.difference_in_place(&odd), 500);
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq((0..1_000).step_by(2)));
+    }
+    #[test]
+    fn intersect_in_place() {
+        let mut l = build(4, [1, 2, 2, 2, 3, 5, 8, 8, 13]);
+        l.intersect_in_place(&build(4, [0, 2, 2, 4, 8, 8, 8, 13, 21]));
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(vec![2, 2, 8, 8, 13]));
+        let mut l = build(4, 0..1_000);
+        l.intersect_in_place(&build(4, [-5, 10, 500, 500, 999, 2_000]));
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(vec![10, 500, 999]));
+        l.intersect_in_place(&SortedList::with_block_size(4));
+        assert!(l.is_empty());
+    }
+    #[test]
+    fn from_iter() {
+        let v = {
+            use rand::prelude::*;
+            let mut v: Vec<u32> = (0..10_000).map(|x| x / 3).collect();
+            v.shuffle(&mut StdRng::seed_from_u64(11));
+            v
+        };
+        let collected: SortedList<u32> = v.iter().copied().collect();
+        let mut inserted = SortedList::with_block_size(100);
+        for x in &v {
+            inserted.insert(*x);
+        }
+        check_invariants(&collected);
+        assert_eq!(collected.block_size, 100);
+        assert!(collected.iter().eq(inserted.iter()));
+        let empty: SortedList<u32> = None.into_iter().collect();
+        assert!(empty.is_empty());
+    }
+    #[test]
+    fn extend() {
+        fn load<E: Extend<u32>>(target: &mut E, values: Vec<u32>) {
+            target.extend(values)
+        }
+        let mut l = SortedList::with_block_size(10);
+        load(&mut l, vec![5, 3, 1]);
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(vec![1, 3, 5]));
+        l.extend((0..100).rev());
+        l.extend(vec![3, 3, 99, 0]);
+        check_invariants(&l);
+        let mut expected: Vec<u32> = (0..100).chain(vec![1, 3, 5, 3, 3, 99, 0]).collect();
+        expected.sort();
+        assert!(l.iter().eq(expected.iter()));
+    }
+    #[test]
+    fn default() {
+        #[derive(Default)]
+        struct Container {
+            list: SortedList<u32>,
+        }
+        let mut c = Container::default();
+        assert!(c.list.is_empty());
+        assert_eq!(c.list.block_size, DEFAULT_BLOCK_SIZE);
+        for x in (0..1_000_000).rev() {
+            c.list.insert(x);
+        }
+        assert!(c.list.data.len() <= 2 * 1_000_000 / DEFAULT_BLOCK_SIZE + 1);
+        assert!(c.list.iter().cloned().eq(0..1_000_000));
+        assert!(c.list.contains(&999_999));
+    }
+    #[test]
+    fn new() {
+        let mut l = SortedList::new();
+        assert_eq!(l.block_size, DEFAULT_BLOCK_SIZE);
+        l.insert(2);
+        l.insert(1);
+        assert!(l.iter().cloned().eq(1..3));
+        assert_eq!(SortedList::<u8>::with_block_size(10).block_size, 10);
+    }
+    #[test]
+    fn from_sorted_vec() {
+        let mut l = SortedList::from_sorted_vec(10, (0..95).map(|e| 2 * e).collect());
+        let mut expected = SortedList::with_block_size(10);
+        for e in 0..95 {
+            expected.insert(2 * e);
+        }
+        check_invariants(&l);
+        assert_eq!(l.len(), 95);
+        assert!(l.data.iter().rev().skip(1).all(|b| b.len() == 10));
+        for e in 0..200 {
+            assert_eq!(l.contains(&e), expected.contains(&e));
+        }
+        for e in (0..190).step_by(3) {
+            assert_eq!(l.remove(&e), expected.remove(&e));
+            l.insert(e + 1);
+            expected.insert(e + 1);
+            check_invariants(&l);
+        }
+        assert!(l.iter().eq(expected.iter()));
+        assert!(SortedList::<u32>::from_sorted_vec(10, Vec::new()).is_empty());
+    }
+    #[test]
+    #[should_panic(expected = "block size must be at least 2")]
+    fn from_sorted_vec_zero_block_size() {
+        SortedList::from_sorted_vec(0, vec![1, 2, 3]);
+    }
+    #[test]
+    #[should_panic(expected = "block size must be at least 2")]
+    fn from_sorted_vec_unit_block_size() {
+        SortedList::from_sorted_vec(1, vec![1, 2, 3]);
+    }
+    #[test]
+    fn smallest_block_size() {
+        for elements in [
+            (0..100).collect::<Vec<_>>(),
+            (0..100).rev().collect(),
+            vec![7; 100],
+        ] {
+            let l = build(2, elements.iter().copied());
+            check_invariants(&l);
+            assert_eq!(l.len(), 100);
+            let mut l = SortedList::from_sorted_vec(2, vec![3; 5]);
+            for x in elements {
+                l.insert(x);
+            }
+            check_invariants(&l);
+        }
+    }
+    #[test]
+    fn insert_duplicates() {
+        let mut l = SortedList::with_block_size(10);
+        for _ in 0..100 {
+            l.insert(3);
+        }
+        check_invariants(&l);
+        assert_eq!(l.len(), 100);
+        assert!(l.data.len() <= 11);
+        l.insert_sorted_vec(vec![1; 10]);
+        for _ in 0..10 {
+            l.insert(2);
+        }
+        check_invariants(&l);
+        assert!(l.data.len() <= 13);
+        for _ in 0..100 {
+            l.insert(1);
+            l.insert(3);
+        }
+        check_invariants(&l);
+        assert_eq!(l.len(), 320);
+        assert!(l.data.len() <= 34);
+        assert_eq!(l.count(&1), 110);
+        assert_eq!(l.count(&2), 10);
+        // splitting a full block, an element equal to the left half's last one goes left
+        for block_size in [4, 5] {
+            let mut l =
+                SortedList::from_sorted_vec(block_size, vec![1, 3, 3, 3, 3][..block_size].to_vec());
+            l.insert(3);
+            check_invariants(&l);
+            assert_eq!(l.data[0], [1, 3, 3]);
+            assert_eq!(l.data[1].len(), block_size - 2);
+        }
+    }
+    #[test]
+    fn from_vec() {
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(12);
+        let v: Vec<u32> = (0..1000).map(|_| rng.gen_range(0..300)).collect();
+        let l = SortedList::from(v.clone());
+        check_invariants(&l);
+        assert_eq!(l.block_size, 32);
+        let mut expected = v;
+        expected.sort();
+        assert!(l.iter().eq(expected.iter()));
+        let l = SortedList::from(vec![2, 1]);
+        assert_eq!(l.block_size, MIN_BLOCK_SIZE);
+        assert!(l.iter().cloned().eq(1..3));
+        assert!(SortedList::<u8>::from(Vec::new()).is_empty());
+    }
+    #[test]
+    fn from_collections() {
+        use std::cmp::Reverse;
+        use std::collections::{BTreeSet, BinaryHeap};
+        let set: BTreeSet<u64> = (0..500).map(|e| (e * 37) % 1000).collect();
+        let l = SortedList::from(set.clone());
+        check_invariants(&l);
+        assert_eq!(l.block_size, 23);
+        assert!(l.iter().eq(set.iter()));
+        let heap: BinaryHeap<Reverse<u64>> = (0..500).map(|e| Reverse((e * 37) % 100)).collect();
+        let l = SortedList::from(heap.clone());
+        check_invariants(&l);
+        assert_eq!(l.len(), 500);
+        assert!(l.iter().eq(heap.into_sorted_vec().iter()));
+        let expected = (0..100).rev().flat_map(|e| std::iter::repeat_n(e, 5));
+        assert!(l.iter().map(|e| e.0).eq(expected));
+        assert!(SortedList::from(BTreeSet::<u8>::new()).is_empty());
+        assert!(SortedList::from(BinaryHeap::<u8>::new()).is_empty());
+    }
+    #[test]
+    fn into_vec() {
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct NoClone(u32);
+        let mut l = SortedList::with_block_size(10);
+        for e in (0..95).rev() {
+            l.insert(NoClone(e));
+        }
+        let v = l.into_vec();
+        assert_eq!(v.len(), 95);
+        assert!(v.iter().map(|e| e.0).eq(0..95));
+        // everything fits in the (last) block
+        let mut l = SortedList::from_sorted_vec(10, (0..13).collect());
+        l.data[1].reserve(20);
+        let capacity = l.data[1].capacity();
+        let v = l.into_vec();
+        assert!(v.iter().cloned().eq(0..13));
+        assert_eq!(v.capacity(), capacity);
+        assert!(SortedList::<NoClone>::new().into_vec().is_empty());
+    }
+    #[test]
+    fn from_array() {
+        let l = SortedList::from([3, 1, 2, 1]);
+        check_invariants(&l);
+        assert_eq!(l.block_size, MIN_BLOCK_SIZE);
+        let mut expected = SortedList::new();
+        for e in [3, 1, 2, 1].iter() {
+            expected.insert(*e);
+        }
+        assert_eq!(l.len(), expected.len());
+        assert!(l.iter().eq(expected.iter()));
+        assert_eq!(l.count(&1), 2);
+        assert!(SortedList::<u8>::from([]).is_empty());
+    }
+    #[test]
+    fn builder() {
+        let l: SortedList<u32> = SortedListBuilder::new().build();
+        assert_eq!(l.block_size, SortedList::<u32>::default().block_size);
+        let l: SortedList<u32> = SortedListBuilder::new().expected_len(10_000).build();
+        assert_eq!(l.block_size, 100);
+        assert!(l.data.capacity() >= 100);
+        let l = SortedListBuilder::new()
+            .expected_len(10_000)
+            .block_size(10)
+            .build_from_sorted((0..95).collect());
+        check_invariants(&l);
+        assert_eq!(l.block_size, 10);
+        assert_eq!(l.data.len(), 10);
+        let mut l = SortedListBuilder::new()
+            .block_size(10)
+            .fill_factor(0.5)
+            .build_from_iter((0..95).rev());
+        check_invariants(&l);
+        assert_eq!(l.data.len(), 19);
+        assert!(l.iter().cloned().eq(0..95));
+        // blocks have room so inserting does not split them
+        l.insert(3);
+        assert_eq!(l.data.len(), 19);
+        let l = SortedListBuilder::new().build_from_iter(vec![3, 1, 2]);
+        assert_eq!(l.block_size, DEFAULT_BLOCK_SIZE);
+        assert_eq!(l.len(), 3);
+    }
+    #[test]
+    #[should_panic(expected = "block size must be at least 2")]
+    fn builder_unit_block_size() {
+        SortedListBuilder::new().block_size(1);
+    }
+    #[test]
+    fn try_from_sorted_vec() {
+        let l = SortedList::try_from_sorted_vec(10, (0..100).collect()).unwrap();
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(0..100));
+        let mut v: Vec<u32> = (0..100).collect();
+        v.push(3);
+        let err = SortedList::try_from_sorted_vec(10, v.clone())
+            .err()
+            .unwrap();
+        assert_eq!(err.index, 100);
+        assert_eq!(err.data, v);
+        assert_eq!(err.to_string(), "elements are not sorted at index 100");
+        let l = SortedList::try_from_sorted_vec(2, vec![1, 2, 2, 2, 3, 3]).unwrap();
+        check_invariants(&l);
+        assert_eq!(l.count(&2), 3);
+        assert!(SortedList::<u8>::try_from_sorted_vec(10, Vec::new())
+            .unwrap()
+            .is_empty());
+    }
+    #[test]
+    fn into_iter() {
+        use std::cell::Cell;
+        #[derive(PartialEq, Eq, PartialOrd, Ord)]
+        struct Counted<'a>(u32, &'a Cell<usize>);
+        impl<'a> Drop for Counted<'a> {
+            fn drop(&mut self) {
+                self.1.set(self.1.get() + 1);
+            }
+        }
+        let mut l = SortedList::with_block_size(10);
+        for e in (0..95).rev() {
+            l.insert(e);
+        }
+        let mut seen = Vec::new();
+        for e in l {
+            seen.push(e);
+        }
+        assert!(seen.into_iter().eq(0..95));
+        let l: SortedList<u32> = (0..3).collect();
+        assert!(vec![l].into_iter().flatten().eq(0..3));
+
+        let drops = Cell::new(0);
+        let mut l = SortedList::with_block_size(10);
+        for e in 0..95 {
+            l.insert(Counted(e, &drops));
+        }
+        let mut iter = l.into_iter();
+        assert_eq!(iter.size_hint(), (95, Some(95)));
+        for e in 0..15 {
+            assert_eq!(iter.next().map(|c| c.0), Some(e));
+        }
+        assert_eq!(drops.get(), 15);
+        assert_eq!(iter.size_hint(), (80, Some(80)));
+        drop(iter);
+        assert_eq!(drops.get(), 95);
+    }
+    #[test]
+    fn ref_into_iter() {
+        let mut l = SortedList::with_block_size(10);
+        for e in (0..95u32).rev() {
+            l.insert(e);
+        }
+        let mut expected = 0;
+        for e in &l {
+            assert_eq!(*e, expected);
+            expected += 1;
+        }
+        assert_eq!(expected, 95);
+        fn total<'a, I: IntoIterator<Item = &'a u32>>(elements: I) -> u32 {
+            elements.into_iter().sum()
+        }
+        assert_eq!(total(&l), (0..95).sum::<u32>());
+        assert_eq!((&l).into_iter().max(), Some(&94));
+        assert!(SortedList::<u32>::new().iter().next().is_none());
+    }
+    #[test]
+    fn iter_rev() {
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(13);
+        let mut l = SortedList::with_block_size(10);
+        for _ in 0..200 {
+            l.insert(rng.gen_range(0..100));
+        }
+        let expected: Vec<u32> = l.iter().cloned().collect();
+        assert!(l.iter().rev().eq(expected.iter().rev()));
+        for _ in 0..10 {
+            let mut iter = l.iter();
+            let mut oracle = expected.iter();
+            loop {
+                let (e, o) = if rng.gen() {
+                    (iter.next(), oracle.next())
+                } else {
+                    (iter.next_back(), oracle.next_back())
+                };
+                assert_eq!(e, o);
+                if e.is_none() {
+                    break;
+                }
+            }
+            assert!(iter.next().is_none() && iter.next_back().is_none());
+        }
+        let single: SortedList<u32> = std::iter::once(3).collect();
+        let mut iter = single.iter();
+        assert_eq!(iter.next_back(), Some(&3));
+        assert_eq!(iter.next(), None);
+        assert!(SortedList::<u32>::new().iter().next_back().is_none());
+    }
+    #[test]
+    fn iter_len() {
+        let mut l = SortedList::with_block_size(10);
+        for e in (0..95).rev() {
+            l.insert(e);
+        }
+        assert_eq!(l.iter().len(), l.len());
+        let mut iter = l.iter();
+        for consumed in 1..=40 {
+            iter.next();
+            iter.next_back();
+            assert_eq!(iter.len(), 95 - 2 * consumed);
+            assert_eq!(iter.size_hint(), (iter.len(), Some(iter.len())));
+        }
+        assert_eq!(iter.by_ref().count(), 15);
+        assert_eq!(iter.len(), 0);
+        let mut iter = l.into_iter();
+        iter.next();
+        assert_eq!(iter.len(), 94);
+    }
+    #[test]
+    fn range() {
+        use rand::prelude::*;
+        use std::collections::BTreeSet;
+        use std::ops::Bound::*;
+        let mut rng = StdRng::seed_from_u64(14);
+        let mut l = SortedList::with_block_size(10);
+        assert!(l.range(..).next().is_none());
+        let mut set = BTreeSet::new();
+        for _ in 0..300 {
+            let e = rng.gen_range(0..1000);
+            if set.insert(e) {
+                l.insert(e);
+            }
+        }
+        for _ in 0..500 {
+            let a = rng.gen_range(-10..1010);
+            let b = rng.gen_range(a..1010);
+            assert!(l.range(a..b).eq(set.range(a..b)));
+            assert!(l.range(a..=b).eq(set.range(a..=b)));
+            assert!(l.range(a..).eq(set.range(a..)));
+            assert!(l.range(..b).eq(set.range(..b)));
+            let bounds = (Excluded(a), Included(b));
+            assert!(l.range(bounds).eq(set.range(bounds)));
+            assert!(l.range(bounds).rev().eq(set.range(bounds).rev()));
+            assert_eq!(l.range(bounds).len(), set.range(bounds).count());
+            // inverted ranges are empty
+            assert!(l.range((Included(b + 1), Excluded(a))).next().is_none());
+        }
+        assert!(l.range::<i32, _>(..).eq(set.iter()));
+    }
+    #[test]
+    fn iter_from() {
+        let mut l = SortedList::with_block_size(10);
+        assert!(l.iter_from(&3).next().is_none());
+        for e in 0..100 {
+            l.insert(e / 3 * 3);
+        }
+        assert!(l.iter_from(&-5).eq(l.iter()));
+        assert!(l.iter_from(&100).next().is_none());
+        assert!(l.iter_from(&99).eq([99; 1].iter()));
+        assert!(l.iter_from(&30).eq(l.iter().skip(30)));
+        assert!(l.iter_from(&31).eq(l.iter().skip(33)));
+        // we start at the first copy
+        assert!(l.iter_from(&9).take(4).eq([9, 9, 9, 12].iter()));
+        assert!(l.iter_from(&27).eq(l.iter().filter(|&&e| e >= 27)));
+    }
+    #[test]
+    fn islice() {
+        let mut l = SortedList::with_block_size(10);
+        assert!(l.islice(0..10).next().is_none());
+        for e in (0..1_000).rev() {
+            l.insert(e);
+        }
+        let v: Vec<i32> = l.iter().cloned().collect();
+        assert!(l.islice(100..120).eq(v[100..120].iter()));
+        assert!(l.islice(3..5).eq(v[3..5].iter()));
+        assert_eq!(l.islice(0..1_000).len(), 1_000);
+        // ends are clamped
+        assert!(l.islice(990..2_000).eq(v[990..].iter()));
+        assert!(l.islice(1_000..2_000).next().is_none());
+        let (start, end) = (7, 5);
+        assert!(l.islice(start..end).next().is_none());
+        assert!(l.islice(995..1_000).rev().eq(v[995..].iter().rev()));
+    }
+    #[test]
+    fn seek_iter() {
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(15);
+        let mut l = SortedList::with_block_size(10);
+        let mut iter = l.seek_iter();
+        iter.seek(&3);
+        assert!(iter.next().is_none());
+        for _ in 0..500 {
+            l.insert(rng.gen_range(0..1000));
+        }
+        assert!(l.seek_iter().eq(l.iter()));
+        for _ in 0..50 {
+            let mut iter = l.seek_iter();
+            let mut oracle = l.iter().peekable();
+            let mut key = 0;
+            loop {
+                if rng.gen() {
+                    key += rng.gen_range(0..100);
+                    iter.seek(&key);
+                    while oracle.next_if(|&&e| e < key).is_some() {}
+                } else {
+                    let e = iter.next();
+                    assert_eq!(e, oracle.next());
+                    match e {
+                        Some(&e) => assert!(e >= key),
+                        None => break,
+                    }
+                }
+            }
+        }
+        // seeking backwards does nothing
+        let mut iter = l.seek_iter();
+        iter.seek(&500);
+        let next = *iter.next().unwrap();
+        iter.seek(&0);
+        assert!(iter.next().is_some_and(|&e| e >= next));
+        iter.seek(&1000);
+        assert!(iter.next().is_none());
+    }
+    #[test]
+    fn cursor() {
+        let mut l = SortedList::with_block_size(10);
+        let mut cursor = l.cursor_at(&3);
+        assert!(cursor.peek().is_none());
+        cursor.move_next();
+        assert!(cursor.peek().is_none());
+        cursor.move_prev();
+        assert!(cursor.rank().is_none());
+        for e in (0..95).rev() {
+            l.insert(e / 2);
+        }
+        let v: Vec<i32> = l.iter().cloned().collect();
+        let mut cursor = l.cursor_at_index(0);
+        for (i, e) in v.iter().enumerate() {
+            assert_eq!(cursor.peek(), Some(e));
+            assert_eq!(cursor.rank(), Some(i));
+            cursor.move_next();
+        }
+        assert!(cursor.peek().is_none());
+        assert!(cursor.rank().is_none());
+        for (i, e) in v.iter().enumerate().rev() {
+            cursor.move_prev();
+            assert_eq!(cursor.peek(), Some(e));
+            assert_eq!(cursor.rank(), Some(i));
+        }
+        // going around through the ghost
+        cursor.move_prev();
+        assert!(cursor.peek().is_none());
+        cursor.move_prev();
+        assert_eq!(cursor.peek(), Some(&47));
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.rank(), Some(0));
+        let cursor = l.cursor_at(&20);
+        assert_eq!(cursor.rank(), Some(40));
+        assert_eq!(cursor.peek(), Some(&20));
+        assert!(l.cursor_at(&100).peek().is_none());
+        assert!(l.cursor_at_index(95).rank().is_none());
+        assert!(l.cursor_at_index(1_000).peek().is_none());
+    }
+    #[test]
+    fn cursor_mut() {
+        let mut l = SortedList::with_block_size(10);
+        for e in (0..200).rev() {
+            l.insert(e);
+        }
+        // remove every other element
+        let mut cursor = l.cursor_mut_at_index(0);
+        let mut removed = Vec::new();
+        while cursor.peek().is_some() {
+            removed.push(cursor.remove_current());
+            cursor.move_next();
+        }
+        check_invariants(&l);
+        assert!(removed.into_iter().eq((0..200).step_by(2)));
+        assert!(l.iter().cloned().eq((1..200).step_by(2)));
+        // insert them back
+        let mut cursor = l.cursor_mut_at_index(0);
+        for e in (0..200).step_by(2) {
+            cursor.insert(e);
+            assert_eq!(cursor.rank(), Some(e as usize + 1));
+            cursor.move_next();
+        }
+        assert!(cursor.peek().is_none());
+        cursor.insert(200);
+        cursor.move_prev();
+        assert_eq!(cursor.remove_current(), 200);
+        assert!(cursor.rank().is_none());
+        check_invariants(&l);
+        assert!(l.iter().cloned().eq(0..200));
+        // removing everything from the end
+        let mut cursor = l.cursor_mut_at(&150);
+        for e in 150..200 {
+            assert_eq!(cursor.remove_current(), e);
+        }
+        assert!(cursor.peek().is_none());
+        cursor.move_prev();
+        while cursor.rank().is_some() {
+            cursor.remove_current();
+            cursor.move_prev();
+        }
+        check_invariants(&l);
+        assert!(l.is_empty());
+        let mut cursor = l.cursor_mut_at(&0);
+        cursor.insert(3);
+        check_invariants(&l);
+        assert_eq!(l.len(), 1);
+    }
+    #[test]
+    #[should_panic(expected = "out of order")]
+    fn cursor_mut_insert_out_of_order() {
+        let mut l: SortedList<i32> = (0..10).collect();
+        l.cursor_mut_at(&5).insert(6);
+    }
+    #[test]
+    fn blocks() {
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.blocks().len(), 0);
+        for e in (0..95u64).rev() {
+            l.insert(e * 7 % 95);
+        }
+        assert!(l.blocks().all(|b| !b.is_empty()));
+        assert_eq!(
+            l.blocks().map(|b| b.iter().sum::<u64>()).sum::<u64>(),
+            l.iter().sum::<u64>()
+        );
+        assert!(l.blocks().flatten().eq(l.iter()));
+    }
+    #[test]
+    fn chunks() {
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.chunks(3).count(), 0);
+        for e in (0..95).rev() {
+            l.insert(e);
+        }
+        let v: Vec<&i32> = l.iter().collect();
+        for size in [1, 3, 7, 10, 20, 94, 95, 96, 1_000].iter() {
+            let chunks: Vec<Vec<&i32>> = l.chunks(*size).map(|c| c.collect()).collect();
+            let expected: Vec<Vec<&i32>> = v.chunks(*size).map(|c| c.to_vec()).collect();
+            assert_eq!(chunks, expected);
+        }
+        assert!(l.chunks(20).all(|c| c.len() == 20 || c.len() == 15));
+    }
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_iter() {
+        let mut l = SortedList::with_block_size(100);
+        for e in (0..10_000u64).rev() {
+            l.insert(e * 7 % 10_000);
+        }
+        assert_eq!(l.par_iter().sum::<u64>(), l.iter().sum::<u64>());
+        let v: Vec<&u64> = l.par_iter().collect();
+        assert!(v.into_iter().eq(l.iter()));
+        assert_eq!(SortedList::<u64>::new().par_iter().count(), 0);
+    }
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_extend() {
+        let mut l = SortedList::with_block_size(100);
+        for e in 0..1_000u32 {
+            l.insert(2 * e);
+        }
+        // each value comes 4 times, from different threads
+        l.par_extend((0..40_000u32).into_par_iter().map(|e| e % 10_000));
+        check_invariants(&l);
+        assert_eq!(l.len(), 41_000);
+        for e in 0..2_000 {
+            assert_eq!(l.count(&e), if e % 2 == 0 { 5 } else { 4 });
+        }
+        assert_eq!(l.count(&9_999), 4);
+        l.par_extend(Vec::new());
+        assert_eq!(l.len(), 41_000);
+    }
+    #[test]
+    fn iter_nth() {
+        let mut l = SortedList::with_block_size(10);
+        for e in (0..95).rev() {
+            l.insert(e);
+        }
+        let v: Vec<i32> = l.iter().cloned().collect();
+        for n in 0..100 {
+            assert_eq!(l.iter().nth(n), v.get(n));
+        }
+        let mut iter = l.iter();
+        let mut oracle = v.iter();
+        for n in 0..20 {
+            assert_eq!(iter.nth(n), oracle.nth(n));
+            assert_eq!(iter.next(), oracle.next());
+            assert_eq!(iter.next_back(), oracle.next_back());
+            assert_eq!(iter.len(), oracle.len());
+        }
+        assert!(l.iter().step_by(7).eq(v.iter().step_by(7)));
+        assert!(l.iter().skip(33).eq(v.iter().skip(33)));
+        let mut iter = l.range(10..20);
+        assert_eq!(iter.nth(15), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.len(), 0);
+    }
+    #[test]
+    fn iter_consumers() {
+        let mut l = SortedList::with_block_size(10);
+        for e in (0..95u64).rev() {
+            l.insert(e);
+        }
+        assert_eq!(l.iter().sum::<u64>(), (0..95).sum::<u64>());
+        assert_eq!(l.iter().count(), 95);
+        assert_eq!(l.iter().last(), Some(&94));
+        let mut iter = l.range(10..50);
+        iter.next();
+        iter.next_back();
+        assert_eq!(iter.len(), 38);
+        assert_eq!(
+            iter.fold(Vec::new(), |mut v, e| {
+                v.push(*e);
+                v
+            }),
+            (11..49).collect::<Vec<_>>()
+        );
+        let mut iter = l.islice(90..95);
+        assert_eq!(iter.by_ref().count(), 5);
+        assert!(iter.next().is_none() && iter.next().is_none());
+        let owned: SortedList<u64> = (0..95).collect();
+        let mut iter = owned.into_iter();
+        iter.next();
+        assert_eq!(iter.len(), 94);
+        assert_eq!(iter.sum::<u64>(), (1..95).sum::<u64>());
+        let owned: SortedList<u64> = (0..95).collect();
+        assert_eq!(owned.into_iter().last(), Some(94));
+        let owned: SortedList<u64> = (0..95).collect();
+        assert_eq!(owned.into_iter().count(), 95);
+    }
+    #[test]
+    fn into_iter_rev() {
+        use std::cell::RefCell;
+        #[derive(PartialEq, Eq, PartialOrd, Ord)]
+        struct Logged<'a>(u32, &'a RefCell<Vec<u32>>);
+        impl<'a> Drop for Logged<'a> {
+            fn drop(&mut self) {
+                self.1.borrow_mut().push(self.0);
+            }
+        }
+        let l: SortedList<u32> = (0..95).collect();
+        assert!(l.into_iter().rev().eq((0..95).rev()));
+        let dropped = RefCell::new(Vec::new());
+        let mut l = SortedList::with_block_size(10);
+        for e in 0..95 {
+            l.insert(Logged(e, &dropped));
+        }
+        let mut iter = l.into_iter();
+        // elements are dropped lazily, as soon as they are yielded
+        assert_eq!(iter.next_back().map(|e| e.0), Some(94));
+        assert_eq!(iter.next().map(|e| e.0), Some(0));
+        assert_eq!(*dropped.borrow(), vec![94, 0]);
+        for e in 1..10 {
+            assert_eq!(iter.next().map(|e| e.0), Some(e));
+        }
+        assert_eq!(iter.len(), 84);
+        drop(iter);
+        // remaining elements are dropped exactly once
+        let mut dropped = dropped.into_inner();
+        dropped.sort();
+        assert!(dropped.into_iter().eq(0..95));
+    }
+    #[test]
+    fn iter_named() {
+        struct Pending<'a> {
+            jobs: Iter<'a, u32>,
+        }
+        fn pending(l: &SortedList<u32>) -> Iter<'_, u32> {
+            l.iter_from(&10)
+        }
+        let l: SortedList<u32> = (0..20).collect();
+        let mut p = Pending { jobs: pending(&l) };
+        assert_eq!(p.jobs.next(), Some(&10));
+        let saved = p.jobs.clone();
+        assert!(p.jobs.cloned().eq(11..20));
+        assert_eq!(saved.len(), 9);
+        let mut iter = l.range(3..7);
+        iter.next_back();
+        assert_eq!(format!("{:?}", iter), "Iter([3, 4, 5])");
+        assert_eq!(format!("{:?}", SortedList::<u8>::new().iter()), "Iter([])");
+    }
+    #[test]
+    fn range_by() {
+        let mut l = SortedList::with_block_size(10);
+        assert!(l.range_by_key(&1, |e: &(u32, u32)| e.0).next().is_none());
+        for id in 0..300 {
+            let category = id % 7;
+            if category != 4 {
+                l.insert((category, id));
+            }
+        }
+        for category in 0..8 {
+            let expected: Vec<&(u32, u32)> = l.iter().filter(|e| e.0 == category).collect();
+            assert!(l
+                .range_by_key(&category, |e| e.0)
+                .eq(expected.iter().cloned()));
+        }
+        assert_eq!(l.range_by_key(&2, |e| e.0).len(), 43);
+        assert!(l.range_by_key(&4, |e| e.0).next().is_none());
+        // a window inside a category
+        let window = l.range_by(|e| (e.0, e.1 / 100).cmp(&(3, 1)));
+        assert!(window.map(|e| e.1).eq((101..200).filter(|id| id % 7 == 3)));
+    }
+    #[test]
+    fn merge() {
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(16);
+        let mut l1 = SortedList::with_block_size(10);
+        let mut l2 = SortedList::with_block_size(7);
+        assert_eq!(l1.merge(&l2).count(), 0);
+        for _ in 0..300 {
+            l1.insert(Keyed(rng.gen_range(0..100), 1));
+            l2.insert(Keyed(rng.gen_range(50..150), 2));
+        }
+        // a stable sort keeps ties from the first list first
+        let mut expected: Vec<(u32, u8)> = l1.iter().chain(l2.iter()).map(|e| (e.0, e.1)).collect();
+        expected.sort_by_key(|e| e.0);
+        assert_eq!(l1.merge(&l2).len(), 600);
+        assert!(l1.merge(&l2).map(|e| (e.0, e.1)).eq(expected.into_iter()));
+        assert!(l1.merge(&SortedList::new()).eq(l1.iter()));
+        assert!(SortedList::new().merge(&l2).eq(l2.iter()));
+    }
+    #[test]
+    fn merge_all() {
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(17);
+        assert!(SortedList::<u32>::merge_all(Vec::new()).is_empty());
+        let mut expected = Vec::new();
+        let mut shards = Vec::new();
+        for shard in 0..20 {
+            // overlapping shards, some disjoint ones and an empty one
+            let (start, end) = if shard < 10 {
+                (0, 1_000)
+            } else {
+                (shard * 1_000, shard * 1_000 + 500)
+            };
+            let mut l = SortedList::with_block_size(10);
+            for _ in 0..(shard * 13) {
+                let e = rng.gen_range(start..end);
+                l.insert(e);
+                expected.push(e);
+            }
+            shards.push(l);
+        }
+        expected.sort();
+        let merged = SortedList::merge_all(shards);
+        check_invariants(&merged);
+        assert_eq!(merged.block_size, block_size_for(expected.len()));
+        assert!(merged.iter().eq(expected.iter()));
+        // ties come from earlier lists first
+        let merged = SortedList::merge_all(vec![
+            SortedList::from(vec![(1, "a")]),
+            SortedList::from(vec![(0, "b"), (1, "b")]),
+        ]);
+        assert!(merged.into_iter().eq(vec![(0, "b"), (1, "a"), (1, "b")]));
+    }
+    #[test]
+    fn adjacent_pairs() {
+        let mut l = SortedList::with_block_size(10);
+        assert_eq!(l.adjacent_pairs().count(), 0);
+        l.insert(3u64);
+        assert_eq!(l.adjacent_pairs().count(), 0);
+        assert_eq!(l.gaps().count(), 0);
+        for e in 1..95 {
+            l.insert(3 + e * e);
+        }
+        assert_eq!(l.adjacent_pairs().count(), l.len() - 1);
+        assert!(l.adjacent_pairs().all(|(a, b)| a < b));
+        // pairs bridging blocks are present
+        for block in 0..l.data.len() - 1 {
+            let bridge = (l.data[block].last().unwrap(), &l.data[block + 1][0]);
+            assert!(l.adjacent_pairs().any(|p| p == bridge));
+        }
+        assert!(l.gaps().eq((1..95).map(|e| 2 * e - 1)));
+    }
+    #[test]
+    fn clone() {
+        let mut l = SortedList::with_block_size(10);
+        for e in (0..95).rev() {
+            l.insert(e);
+        }
+        let mut c = l.clone();
+        check_invariants(&c);
+        assert_eq!(c.block_size, 10);
+        assert!(c.blocks().eq(l.blocks()));
+        c.remove(&3);
+        c.insert(200);
+        assert!(l.iter().cloned().eq(0..95));
+        assert_eq!(c.len(), 95);
+        // clone_from reuses our blocks
+        let mut target = SortedList::with_block_size(10);
+        for e in 0..300 {
+            target.insert(e);
+        }
+        let first_block = target.data[0].as_ptr();
+        target.clone_from(&l);
+        check_invariants(&target);
+        assert_eq!(target.data[0].as_ptr(), first_block);
+        assert!(target.blocks().eq(l.blocks()));
+        let mut small = SortedList::with_block_size(3);
+        small.insert(1);
+        small.clone_from(&c);
+        check_invariants(&small);
+        assert_eq!(small.block_size, 10);
+        assert!(small.iter().eq(c.iter()));
+    }
+    #[test]
+    fn debug() {
+        let mut l = SortedList::with_block_size(4);
+        assert_eq!(format!("{:?}", l), "[]");
+        for e in (0..6).rev() {
+            l.insert(e);
+        }
+        assert_eq!(
+            format!("{:?}", l),
+            format!("{:?}", (0..6).collect::<Vec<_>>())
+        );
+        let strings: SortedList<String> = vec!["b".to_string(), "a".to_string()].into();
+        assert_eq!(format!("{:?}", strings), r#"["a", "b"]"#);
+        let pretty = format!("{:#?}", l);
+        assert!(pretty.starts_with("SortedList {\n    len: 6,\n    block_size: 4,\n"));
+        assert_eq!(pretty.matches("Block {").count(), l.data.len());
+        assert!(pretty.contains("first: Some(\n                0,\n            ),"));
+    }
+    #[test]
+    fn eq() {
+        let mut l1 = SortedList::with_block_size(10);
+        let mut l2 = SortedList::with_block_size(3);
+        assert_eq!(l1, l2);
+        for e in 0..100 {
+            l1.insert(e % 30);
+            l2.insert((99 - e) % 30);
+        }
+        assert_ne!(l1.blocks().len(), l2.blocks().len());
+        assert_eq!(l1, l2);
+        assert_eq!(l1, (0..100).map(|e| e % 30).collect::<SortedList<_>>());
+        // same length, one duplicate count differs
+        l1.remove(&5);
+        l1.insert(6);
+        assert_eq!(l1.len(), l2.len());
+        assert_ne!(l1, l2);
+        l2.remove(&5);
+        assert_ne!(l1, l2);
+    }
+    #[test]
+    fn eq_slices() {
+        let mut l = SortedList::with_block_size(3);
+        assert_eq!(l, Vec::new());
+        assert_eq!(l, &[][..]);
+        for e in (0..10).rev() {
+            l.insert(e);
+        }
+        let v: Vec<i32> = (0..10).collect();
+        assert_eq!(l, v);
+        assert_eq!(v, l);
+        assert_eq!(l, &v[..]);
+        assert_eq!(&v[..], l);
+        assert_eq!(l, v[..]);
+        assert_eq!(v[..], l);
+        // same length, different content
+        let mut w = v.clone();
+        w[9] = 10;
+        assert_ne!(l, w);
+        assert_ne!(w, l);
+        // prefixes
+        assert_ne!(l, &v[..9]);
+        assert_ne!(&v[..9], l);
+        w.truncate(9);
+        w.extend(9..11);
+        assert_ne!(l, w);
+    }
+    #[test]
+    fn hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+        let hash = |l: &SortedList<u32>| {
+            let mut hasher = DefaultHasher::new();
+            l.hash(&mut hasher);
+            hasher.finish()
+        };
+        let mut l1 = SortedList::with_block_size(10);
+        let mut l2 = SortedList::with_block_size(3);
+        assert_eq!(hash(&l1), hash(&l2));
+        for e in 0..100 {
+            l1.insert(e % 30);
+            l2.insert((99 - e) % 30);
+        }
+        assert_ne!(l1.blocks().len(), l2.blocks().len());
+        assert_eq!(hash(&l1), hash(&l2));
+        let l3: SortedList<u32> = (0..100).map(|e| e % 30).collect();
+        assert_eq!(hash(&l1), hash(&l3));
+        l2.remove(&5);
+        assert_ne!(hash(&l1), hash(&l2));
+        let set: std::collections::HashSet<SortedList<u32>> =
+            vec![l1, l2, l3].into_iter().collect();
+        assert_eq!(set.len(), 2);
+    }
+    #[test]
+    fn ord() {
+        let l = |v: Vec<u32>| SortedList::from(v);
+        assert!(l(vec![]) < l(vec![0]));
+        // an equal prefix sorts first
+        assert!(l(vec![1, 2]) < l(vec![1, 2, 3]));
+        assert!(l(vec![1, 2, 3]) > l(vec![1, 2]));
+        // only the last element differs
+        assert!(l((0..100).collect()) < l((0..99).chain(Some(200)).collect()));
+        assert!(l(vec![1, 5]) > l(vec![1, 2, 3]));
+        assert_eq!(l(vec![2, 1]).cmp(&l(vec![1, 2])), Ordering::Equal);
+        let mut small_blocks = SortedList::with_block_size(2);
+        small_blocks.insert_sorted_vec((0..10).collect());
+        assert_eq!(small_blocks.cmp(&l((0..10).collect())), Ordering::Equal);
+        assert_eq!(
+            small_blocks.partial_cmp(&l((0..10).collect())),
+            Some(Ordering::Equal)
+        );
+        let mut map = std::collections::BTreeMap::new();
+        map.insert(l(vec![3]), "c");
+        map.insert(l(vec![1, 2]), "b");
+        map.insert(l(vec![1]), "a");
+        assert!(map.values().cloned().eq(vec!["a", "b", "c"]));
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let mut l = SortedList::with_block_size(10);
+        for e in (0..95u64).rev() {
+            l.insert(e * 3);
+        }
+        let json = serde_json::to_string(&l).unwrap();
+        assert_eq!(
+            json,
+            serde_json::to_string(&l.iter().collect::<Vec<_>>()).unwrap()
+        );
+        let mut from_json: SortedList<u64> = serde_json::from_str(&json).unwrap();
+        check_invariants(&from_json);
+        assert_eq!(from_json, l);
+        assert_eq!(from_json.block_size, DEFAULT_BLOCK_SIZE);
+        from_json.insert(4);
+        assert!(from_json.remove(&3));
+        assert!(from_json.contains(&4));
+        let bytes = bincode::serialize(&l).unwrap();
+        let mut from_bincode: SortedList<u64> = bincode::deserialize(&bytes).unwrap();
+        check_invariants(&from_bincode);
+        assert_eq!(from_bincode, l);
+        assert_eq!(from_bincode.block_size, block_size_for(95));
+        from_bincode.insert(4);
+        check_invariants(&from_bincode);
+        let empty: SortedList<u64> = serde_json::from_str("[]").unwrap();
+        assert!(empty.is_empty());
+        assert!(serde_json::from_str::<SortedList<u64>>("[1, \"a\"]").is_err());
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_unsorted() {
+        let l: SortedList<u32> = serde_json::from_str("[1, 2, 2, 2, 3, 3]").unwrap();
+        check_invariants(&l);
+        assert_eq!(l.count(&2), 3);
+        let err = serde_json::from_str::<SortedList<u32>>("[1, 2, 5, 4]").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("sequence is not sorted at index 3"));
+        let err = serde_json::from_str::<SortedList<u32>>("[3, 1]").unwrap_err();
+        assert!(err.to_string().contains("index 1"));
+        let bytes = bincode::serialize(&vec![1u32, 0]).unwrap();
+        assert!(bincode::deserialize::<SortedList<u32>>(&bytes).is_err());
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_forged_length() {
+        // a sequence announcing u64::MAX elements but holding only two
+        let mut bytes = u64::MAX.to_le_bytes().to_vec();
+        bytes.extend(1u64.to_le_bytes());
+        bytes.extend(2u64.to_le_bytes());
+        assert!(bincode::deserialize::<SortedList<u64>>(&bytes).is_err());
+        let mut bytes = bincode::serialize(&vec![1u8, 2]).unwrap();
+        bytes[..8].copy_from_slice(&(1u64 << 40).to_le_bytes());
+        assert!(bincode::deserialize::<SortedList<u8>>(&bytes).is_err());
+    }
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn rkyv() {
+        use rkyv::Deserialize;
+        let mut l = SortedList::with_block_size(10);
+        for e in (0..95u64).rev() {
+            l.insert(e * 3);
+        }
+        let bytes = rkyv::to_bytes::<_, 256>(&l).unwrap();
+        let archived = unsafe { rkyv::archived_root::<SortedList<u64>>(&bytes[..]) };
+        assert_eq!(archived.len(), 95);
+        assert!(archived.as_slice().iter().eq(l.iter()));
+        assert!(archived.blocks().eq(l.blocks()));
+        for e in 0..300 {
+            assert_eq!(archived.contains(&e), l.contains(&e));
+        }
+        assert!(archived.range(10..=30).iter().eq(l.range(10..=30)));
+        assert!(archived.range(100..).iter().eq(l.range(100..)));
+        let (start, end) = (30, 10);
+        assert!(archived.range(start..end).is_empty());
+        let mut back: SortedList<u64> = archived.deserialize(&mut rkyv::Infallible).unwrap();
+        check_invariants(&back);
+        assert_eq!(back, l);
+        assert!(back.blocks().eq(l.blocks()));
+        back.insert(4);
+        check_invariants(&back);
+        let empty = SortedList::<u64>::new();
+        let bytes = rkyv::to_bytes::<_, 256>(&empty).unwrap();
+        let archived = unsafe { rkyv::archived_root::<SortedList<u64>>(&bytes[..]) };
+        assert!(archived.is_empty() && !archived.contains(&3));
+    }
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(18);
+        let mut block_sizes = std::collections::HashSet::new();
+        for _ in 0..100 {
+            let bytes: Vec<u8> = (0..rng.gen_range(0..2_000)).map(|_| rng.gen()).collect();
+            let mut u = Unstructured::new(&bytes);
+            let l = SortedList::<u16>::arbitrary(&mut u).unwrap();
+            check_invariants(&l);
+            assert!((2..=64).contains(&l.block_size));
+            block_sizes.insert(l.block_size);
+        }
+        assert!(block_sizes.len() > 10);
+        let l = SortedList::<u16>::arbitrary(&mut Unstructured::new(&[])).unwrap();
+        assert!(l.is_empty());
+        assert_eq!(SortedList::<u16>::size_hint(0), (1, None));
+    }
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(2_000))]
+
+        #[test]
+        fn proptest_iteration_is_sorted(l in proptest::prelude::any::<SortedList<i16>>()) {
+            check_invariants(&l);
+            proptest::prop_assert!((2..=64).contains(&l.block_size));
+            proptest::prop_assert!(l.iter().zip(l.iter().skip(1)).all(|(a, b)| a <= b));
+            proptest::prop_assert_eq!(l.iter().rev().count(), l.len());
+        }
+
+        #[test]
+        fn proptest_len(
+            mut l in proptest::prelude::any::<SortedList<u8>>(),
+            operations in proptest::collection::vec((proptest::prelude::any::<bool>(), 0..32u8), 0..200),
+        ) {
+            let initial_len = l.len();
+            let (mut inserted, mut removed) = (0, 0);
+            for (insert, value) in operations {
+                if insert {
+                    l.insert(value);
+                    inserted += 1;
+                } else if l.remove(&value) {
+                    removed += 1;
+                }
+            }
+            check_invariants(&l);
+            proptest::prop_assert_eq!(l.len(), initial_len + inserted - removed);
+            proptest::prop_assert_eq!(l.iter().count(), l.len());
+        }
+
+        #[test]
+        fn proptest_contains(
+            mut l in proptest::prelude::any::<SortedList<u8>>(),
+            operations in proptest::collection::vec((proptest::prelude::any::<bool>(), 0..32u8), 0..200),
+        ) {
+            let mut model: std::collections::HashSet<u8> = l.iter().copied().collect();
+            for (insert, value) in operations {
+                if insert {
+                    l.insert(value);
+                    model.insert(value);
+                } else {
+                    l.remove_all(&value);
+                    model.remove(&value);
+                }
+            }
+            check_invariants(&l);
+            for value in 0..=u8::MAX {
+                proptest::prop_assert_eq!(l.contains(&value), model.contains(&value));
+            }
+        }
+    }
+    #[test]
+    fn set_operators() {
+        use rand::prelude::*;
+        use std::collections::BTreeMap;
+        fn counts(l: &SortedList<u8>) -> BTreeMap<u8, usize> {
+            let mut counts = BTreeMap::new();
+            for e in l {
+                *counts.entry(*e).or_insert(0) += 1;
+            }
+            counts
+        }
+        fn model(
+            a: &BTreeMap<u8, usize>,
+            b: &BTreeMap<u8, usize>,
+            multiplicity: fn(usize, usize) -> usize,
+        ) -> Vec<u8> {
+            (0..=u8::MAX)
+                .flat_map(|v| {
+                    let k = a.get(&v).copied().unwrap_or(0);
+                    let j = b.get(&v).copied().unwrap_or(0);
+                    std::iter::repeat_n(v, multiplicity(k, j))
+                })
+                .collect()
+        }
+        let mut rng = StdRng::seed_from_u64(19);
+        for _ in 0..200 {
+            let mut a = SortedList::with_block_size(rng.gen_range(2..10));
+            let mut b = SortedList::with_block_size(rng.gen_range(2..10));
+            let max = rng.gen_range(1..40);
+            a.extend((0..rng.gen_range(0..100)).map(|_| rng.gen_range(0..max)));
+            b.extend((0..rng.gen_range(0..100)).map(|_| rng.gen_range(0..max)));
+            let (ca, cb) = (counts(&a), counts(&b));
+            let results = [
+                (&a | &b, model(&ca, &cb, usize::max)),
+                (&a & &b, model(&ca, &cb, usize::min)),
+                (&a - &b, model(&ca, &cb, usize::saturating_sub)),
+                (&a ^ &b, model(&ca, &cb, usize::abs_diff)),
+            ];
+            for (result, expected) in results {
+                check_invariants(&result);
+                assert_eq!(result.block_size, a.block_size);
+                assert_eq!(result, expected);
+            }
+        }
+        let a: SortedList<u8> = vec![1, 1, 2, 3, 3, 3].into();
+        let b: SortedList<u8> = vec![1, 3, 4, 4].into();
+        assert_eq!(&a | &b, [1, 1, 2, 3, 3, 3, 4, 4][..]);
+        assert_eq!(&a & &b, [1, 3][..]);
+        assert_eq!(&a - &b, [1, 2, 3, 3][..]);
+        assert_eq!(&a ^ &b, [1, 2, 3, 3, 4, 4][..]);
+        assert!((&a & &SortedList::new()).is_empty());
     }
 }
@@ -0,0 +1,155 @@
+//! Rkyv support: a `SortedList` is archived as a flat sorted slice plus block offsets,
+//! which can be searched directly in the archived bytes.
+use super::{Iter, SortedList};
+use rkyv::ser::{ScratchSpace, Serializer};
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{out_field, Archive, Archived, Deserialize, Fallible, Serialize};
+use std::borrow::Borrow;
+use std::ops::{Bound, RangeBounds};
+
+/// Archived form of a `SortedList`.
+pub struct ArchivedSortedList<T: Archive> {
+    elements: ArchivedVec<Archived<T>>,
+    block_ends: ArchivedVec<Archived<u64>>,
+    block_size: Archived<u64>,
+}
+
+/// Resolver for an archived `SortedList`.
+pub struct SortedListResolver {
+    elements: VecResolver,
+    block_ends: VecResolver,
+}
+
+impl<T: Archive> Archive for SortedList<T> {
+    type Archived = ArchivedSortedList<T>;
+    type Resolver = SortedListResolver;
+
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        let (fp, fo) = out_field!(out.elements);
+        ArchivedVec::resolve_from_len(self.len, pos + fp, resolver.elements, fo);
+        let (fp, fo) = out_field!(out.block_ends);
+        ArchivedVec::resolve_from_len(self.data.len(), pos + fp, resolver.block_ends, fo);
+        let (fp, fo) = out_field!(out.block_size);
+        (self.block_size as u64).resolve(pos + fp, (), fo);
+    }
+}
+
+impl<T, S> Serialize<S> for SortedList<T>
+where
+    T: Serialize<S>,
+    S: Serializer + ScratchSpace + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        let block_ends: Vec<u64> = self
+            .data
+            .iter()
+            .scan(0, |end, block| {
+                *end += block.len() as u64;
+                Some(*end)
+            })
+            .collect();
+        Ok(SortedListResolver {
+            elements: ArchivedVec::serialize_from_iter::<T, _, _, _>(Iter::new(self), serializer)?,
+            block_ends: ArchivedVec::serialize_from_slice(&block_ends, serializer)?,
+        })
+    }
+}
+
+/// Rebuild the same blocks.
+impl<T, D> Deserialize<SortedList<T>, D> for ArchivedSortedList<T>
+where
+    T: Archive,
+    Archived<T>: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<SortedList<T>, D::Error> {
+        let block_size = self.block_size as usize;
+        let mut data = Vec::with_capacity(self.block_ends.len());
+        let mut start = 0;
+        for &end in self.block_ends.iter() {
+            let mut block = Vec::with_capacity(block_size);
+            for element in &self.elements[start..end as usize] {
+                block.push(element.deserialize(deserializer)?);
+            }
+            data.push(block);
+            start = end as usize;
+        }
+        Ok(SortedList {
+            data,
+            block_size,
+            len: self.elements.len(),
+        })
+    }
+}
+
+impl<T: Archive> ArchivedSortedList<T>
+where
+    Archived<T>: Ord,
+{
+    /// Return the number of elements.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Return true if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Return all elements, in order.
+    pub fn as_slice(&self) -> &[Archived<T>] {
+        self.elements.as_slice()
+    }
+
+    /// Iterate in order on the blocks the list had when archived.
+    pub fn blocks(&self) -> impl Iterator<Item = &[Archived<T>]> {
+        let starts = std::iter::once(0).chain(self.block_ends.iter().map(|&end| end as usize));
+        starts
+            .zip(self.block_ends.iter())
+            .map(move |(start, &end)| &self.elements[start..end as usize])
+    }
+
+    /// Return true if an element equal to given value is stored.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        Q: Ord + ?Sized,
+        Archived<T>: Borrow<Q>,
+    {
+        let index = self.partition_point(|e| e.borrow() < value);
+        self.elements
+            .get(index)
+            .is_some_and(|e| e.borrow() == value)
+    }
+
+    /// Return all elements in given range of values, in order.
+    pub fn range<Q, R>(&self, range: R) -> &[Archived<T>]
+    where
+        Q: Ord + ?Sized,
+        Archived<T>: Borrow<Q>,
+        R: RangeBounds<Q>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(value) => self.partition_point(|e| e.borrow() < value),
+            Bound::Excluded(value) => self.partition_point(|e| e.borrow() <= value),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(value) => self.partition_point(|e| e.borrow() <= value),
+            Bound::Excluded(value) => self.partition_point(|e| e.borrow() < value),
+            Bound::Unbounded => self.len(),
+        };
+        &self.elements[start..end.max(start)]
+    }
+
+    /// Return the index of the first element not satisfying given predicate,
+    /// searching first on blocks' last elements, then inside one block.
+    fn partition_point<P: FnMut(&Archived<T>) -> bool>(&self, mut is_before: P) -> usize {
+        let ends = self.block_ends.as_slice();
+        let block = ends.partition_point(|&end| is_before(&self.elements[end as usize - 1]));
+        let start = block
+            .checked_sub(1)
+            .map_or(0, |previous| ends[previous] as usize);
+        let end = ends.get(block).map_or(self.len(), |&end| end as usize);
+        start + self.elements[start..end].partition_point(is_before)
+    }
+}
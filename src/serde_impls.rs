@@ -0,0 +1,60 @@
+//! Serde support: a `SortedList` is (de)serialized as a plain ordered sequence of elements.
+use super::{block_size_for, SortedList, DEFAULT_BLOCK_SIZE};
+use serde::de::{Deserialize, Deserializer, Error, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+impl<T: Serialize> Serialize for SortedList<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len))?;
+        for element in self.data.iter().flatten() {
+            seq.serialize_element(element)?;
+        }
+        seq.end()
+    }
+}
+
+/// Blocks are filled directly while reading the sequence.
+/// The block size is picked from the length when the format gives it,
+/// capping it so that a forged length cannot trigger huge allocations.
+/// Unsorted sequences are rejected with an error giving the first out of order position.
+impl<'de, T: Ord + Deserialize<'de>> Deserialize<'de> for SortedList<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(SortedListVisitor(PhantomData))
+    }
+}
+
+struct SortedListVisitor<T>(PhantomData<T>);
+
+/// Like serde's own (private) `size_hint::cautious`: never trust a length hint
+/// for more than 1MiB of elements.
+fn cautious<T>(len: usize) -> usize {
+    const MAX_PREALLOCATED_BYTES: usize = 1024 * 1024;
+    len.min(MAX_PREALLOCATED_BYTES / std::mem::size_of::<T>().max(1))
+}
+
+impl<'de, T: Ord + Deserialize<'de>> Visitor<'de> for SortedListVisitor<T> {
+    type Value = SortedList<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a sorted sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let block_size = seq
+            .size_hint()
+            .map_or(DEFAULT_BLOCK_SIZE, |len| block_size_for(cautious::<T>(len)));
+        let mut list = SortedList::with_block_size(block_size);
+        while let Some(element) = seq.next_element()? {
+            if list.last().is_some_and(|last| *last > element) {
+                return Err(A::Error::custom(format_args!(
+                    "sequence is not sorted at index {}",
+                    list.len
+                )));
+            }
+            list.extend_sorted_blocks(std::iter::once(element));
+        }
+        Ok(list)
+    }
+}